@@ -0,0 +1,45 @@
+//! Deserializers for every program account type.
+
+use anchor_lang::{AccountDeserialize, Result};
+
+pub use royalties::state::{
+    Auction, ListingStatus, Offer, PayoutClaim, PayoutPool, PlatformConfig, ProceedsEscrow,
+    ProceedsRelease, ResaleListing, RoyaltyListing, SaleMode,
+};
+
+/// Deserialize raw account data, checking the account discriminator
+pub fn deserialize<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn platform_config(data: &[u8]) -> Result<PlatformConfig> {
+    deserialize(data)
+}
+
+pub fn royalty_listing(data: &[u8]) -> Result<RoyaltyListing> {
+    deserialize(data)
+}
+
+pub fn resale_listing(data: &[u8]) -> Result<ResaleListing> {
+    deserialize(data)
+}
+
+pub fn auction(data: &[u8]) -> Result<Auction> {
+    deserialize(data)
+}
+
+pub fn offer(data: &[u8]) -> Result<Offer> {
+    deserialize(data)
+}
+
+pub fn proceeds_escrow(data: &[u8]) -> Result<ProceedsEscrow> {
+    deserialize(data)
+}
+
+pub fn payout_pool(data: &[u8]) -> Result<PayoutPool> {
+    deserialize(data)
+}
+
+pub fn payout_claim(data: &[u8]) -> Result<PayoutClaim> {
+    deserialize(data)
+}
@@ -0,0 +1,786 @@
+//! Instruction builders for every program instruction.
+//!
+//! PDAs are derived from the given keys. Token accounts not owned by the program
+//! are the owners' associated token accounts; escrow and pool vault accounts are
+//! passed in explicitly. Royalty NFTs are Token-2022 mints; the payment mint is
+//! described by a [`PaymentMint`], and for SOL listings the payment token
+//! accounts, the mint and the pool vault are omitted.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::{token, token_2022};
+use royalties::instructions::{
+    CreateCollectionArgs, CreateListingArgs, UpdateConfigArgs, UpdateListingArgs,
+};
+use royalties::state::{ProceedsRelease, SaleMode};
+use royalties::{accounts, instruction};
+
+use crate::pda;
+
+/// Mint a listing is priced in, with the token program that owns it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaymentMint {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl PaymentMint {
+    /// Mint owned by the Token program (e.g. USDC)
+    pub fn token(mint: Pubkey) -> Self {
+        Self {
+            mint,
+            token_program: token::ID,
+        }
+    }
+
+    /// Mint owned by the Token-2022 program
+    pub fn token_2022(mint: Pubkey) -> Self {
+        Self {
+            mint,
+            token_program: token_2022::ID,
+        }
+    }
+
+    /// Native SOL: payments settle in lamports
+    pub fn sol() -> Self {
+        Self::token(native_mint::ID)
+    }
+
+    pub fn is_sol(&self) -> bool {
+        self.mint == native_mint::ID
+    }
+
+    /// Owner's associated token account for the mint (None for SOL)
+    pub fn account(&self, owner: &Pubkey) -> Option<Pubkey> {
+        self.unless_sol(get_associated_token_address_with_program_id(
+            owner,
+            &self.mint,
+            &self.token_program,
+        ))
+    }
+
+    /// `account`, omitted for SOL listings
+    fn unless_sol(&self, account: Pubkey) -> Option<Pubkey> {
+        (!self.is_sol()).then_some(account)
+    }
+}
+
+/// Owner's associated token account for a royalty NFT mint
+pub fn nft_account(owner: &Pubkey, nft_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, nft_mint, &token_2022::ID)
+}
+
+/// Proceeds escrow and its token vault, when the listing escrows sale proceeds
+fn proceeds(
+    royalty_listing: &Pubkey,
+    payment: PaymentMint,
+    escrowed: bool,
+) -> (Option<Pubkey>, Option<Pubkey>) {
+    if !escrowed {
+        return (None, None);
+    }
+    let escrow = pda::proceeds_escrow(royalty_listing).0;
+    (Some(escrow), payment.account(&escrow))
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: royalties::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn initialize(authority: Pubkey, treasury: Pubkey, platform_fee_bps: u16) -> Instruction {
+    build(
+        accounts::Initialize {
+            authority,
+            platform_config: pda::platform_config().0,
+            treasury,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize { platform_fee_bps },
+    )
+}
+
+pub fn update_config(authority: Pubkey, args: UpdateConfigArgs) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            authority,
+            platform_config: pda::platform_config().0,
+        },
+        instruction::UpdateConfig { args },
+    )
+}
+
+pub fn propose_authority(authority: Pubkey, new_authority: Pubkey) -> Instruction {
+    build(
+        accounts::ProposeAuthority {
+            authority,
+            platform_config: pda::platform_config().0,
+        },
+        instruction::ProposeAuthority { new_authority },
+    )
+}
+
+pub fn accept_authority(new_authority: Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAuthority {
+            new_authority,
+            platform_config: pda::platform_config().0,
+        },
+        instruction::AcceptAuthority {},
+    )
+}
+
+pub fn add_payment_mint(authority: Pubkey, payment_mint: Pubkey) -> Instruction {
+    build(
+        accounts::AddPaymentMint {
+            authority,
+            platform_config: pda::platform_config().0,
+            payment_mint,
+        },
+        instruction::AddPaymentMint {},
+    )
+}
+
+pub fn remove_payment_mint(authority: Pubkey, payment_mint: Pubkey) -> Instruction {
+    build(
+        accounts::RemovePaymentMint {
+            authority,
+            platform_config: pda::platform_config().0,
+        },
+        instruction::RemovePaymentMint { payment_mint },
+    )
+}
+
+/// `collection_mint` is a fresh keypair that must also sign the transaction
+pub fn create_collection(
+    authority: Pubkey,
+    collection_mint: Pubkey,
+    args: CreateCollectionArgs,
+) -> Instruction {
+    build(
+        accounts::CreateCollection {
+            authority,
+            platform_config: pda::platform_config().0,
+            collection_mint,
+            collection_metadata: pda::metadata(&collection_mint).0,
+            collection_master_edition: pda::master_edition(&collection_mint).0,
+            authority_collection_nft: nft_account(&authority, &collection_mint),
+            token_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateCollection { args },
+    )
+}
+
+/// `nft_mint` is a fresh keypair that must also sign the transaction.
+/// `collection_mint` is the platform collection, if one has been created.
+pub fn create_listing(
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    collection_mint: Option<Pubkey>,
+    args: CreateListingArgs,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let auction = matches!(args.sale_mode, SaleMode::EnglishAuction { .. });
+    let escrowed = args.proceeds_release != ProceedsRelease::Immediate;
+    build(
+        accounts::CreateListing {
+            creator,
+            platform_config: pda::platform_config().0,
+            royalty_listing,
+            payout_pool: pda::payout_pool(&royalty_listing).0,
+            auction: auction.then(|| pda::auction(&royalty_listing).0),
+            proceeds_escrow: escrowed.then(|| pda::proceeds_escrow(&royalty_listing).0),
+            nft_mint,
+            metadata: pda::metadata(&nft_mint).0,
+            collection_mint,
+            collection_metadata: collection_mint.map(|mint| pda::metadata(&mint).0),
+            collection_master_edition: collection_mint.map(|mint| pda::master_edition(&mint).0),
+            token_program: token_2022::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateListing { args },
+    )
+}
+
+/// `auction` must be set for English auction listings, whose bids lock the terms
+pub fn update_listing(
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    args: UpdateListingArgs,
+    auction: bool,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::UpdateListing {
+            creator,
+            royalty_listing,
+            auction: auction.then(|| pda::auction(&royalty_listing).0),
+            metadata: pda::metadata(&nft_mint).0,
+            token_metadata_program: mpl_token_metadata::ID,
+        },
+        instruction::UpdateListing { args },
+    )
+}
+
+/// `auction` must be set for English auction listings, whose bid state is closed too, and
+/// `escrowed_proceeds` for listings whose proceeds release isn't immediate
+pub fn cancel_listing(
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    auction: bool,
+    escrowed_proceeds: bool,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::CancelListing {
+            creator,
+            royalty_listing,
+            payout_pool: pda::payout_pool(&royalty_listing).0,
+            auction: auction.then(|| pda::auction(&royalty_listing).0),
+            proceeds_escrow: escrowed_proceeds.then(|| pda::proceeds_escrow(&royalty_listing).0),
+            nft_mint,
+            token_program: token_2022::ID,
+        },
+        instruction::CancelListing {},
+    )
+}
+
+/// `escrowed_proceeds` must be set for listings whose proceeds release isn't immediate.
+pub fn close_primary_sale(
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    escrowed_proceeds: bool,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::ClosePrimarySale {
+            creator,
+            royalty_listing,
+            proceeds_escrow: escrowed_proceeds.then(|| pda::proceeds_escrow(&royalty_listing).0),
+        },
+        instruction::ClosePrimarySale {},
+    )
+}
+
+/// `previous_bidder` is the current highest bidder, refunded by this bid
+/// (None for the first bid)
+pub fn place_bid(
+    bidder: Pubkey,
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    payment: PaymentMint,
+    amount: u64,
+    previous_bidder: Option<Pubkey>,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let auction = pda::auction(&royalty_listing).0;
+    build(
+        accounts::PlaceBid {
+            bidder,
+            royalty_listing,
+            auction,
+            auction_vault: payment.account(&auction),
+            bidder_usdc: payment.account(&bidder),
+            previous_bidder: previous_bidder.unwrap_or(bidder),
+            previous_bidder_usdc: previous_bidder.and_then(|previous| payment.account(&previous)),
+            usdc_mint: payment.unless_sol(payment.mint),
+            token_program: payment.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::PlaceBid { amount },
+    )
+}
+
+/// Permissionless; `payer` funds the winner's NFT account and payout position
+pub fn settle_auction(
+    payer: Pubkey,
+    winner: Pubkey,
+    creator: Pubkey,
+    treasury: Pubkey,
+    nft_mint: Pubkey,
+    payment: PaymentMint,
+    escrowed_proceeds: bool,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let auction = pda::auction(&royalty_listing).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    let (proceeds_escrow, proceeds_vault) = proceeds(&royalty_listing, payment, escrowed_proceeds);
+    build(
+        accounts::SettleAuction {
+            payer,
+            creator,
+            winner,
+            platform_config: pda::platform_config().0,
+            treasury,
+            royalty_listing,
+            auction,
+            auction_vault: payment.account(&auction),
+            nft_mint,
+            payout_pool,
+            winner_payout_claim: pda::payout_claim(&payout_pool, &winner).0,
+            winner_nft: nft_account(&winner, &nft_mint),
+            winner_usdc: payment.account(&winner),
+            creator_usdc: payment.account(&creator),
+            treasury_usdc: payment.account(&treasury),
+            proceeds_escrow,
+            proceeds_vault,
+            usdc_mint: payment.unless_sol(payment.mint),
+            metadata: pda::metadata(&nft_mint).0,
+            master_edition: pda::master_edition(&nft_mint).0,
+            token_program: token_2022::ID,
+            payment_token_program: payment.token_program,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+        },
+        instruction::SettleAuction {},
+    )
+}
+
+pub fn expire_listing(creator: Pubkey, nft_mint: Pubkey) -> Instruction {
+    build(
+        accounts::ExpireListing {
+            royalty_listing: pda::royalty_listing(&creator, &nft_mint).0,
+        },
+        instruction::ExpireListing {},
+    )
+}
+
+/// The purchase fails with `PriceMismatch` unless the listing still charges
+/// `expected_price` per share and the total stays within `max_total_cost`.
+/// `escrowed_proceeds` must be set for listings whose proceeds release isn't immediate.
+#[allow(clippy::too_many_arguments)]
+pub fn buy_listing(
+    buyer: Pubkey,
+    creator: Pubkey,
+    treasury: Pubkey,
+    nft_mint: Pubkey,
+    payment: PaymentMint,
+    amount: u64,
+    expected_price: u64,
+    max_total_cost: u64,
+    proof: Vec<[u8; 32]>,
+    escrowed_proceeds: bool,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    let (proceeds_escrow, proceeds_vault) = proceeds(&royalty_listing, payment, escrowed_proceeds);
+    build(
+        accounts::BuyListing {
+            buyer,
+            creator,
+            platform_config: pda::platform_config().0,
+            treasury,
+            royalty_listing,
+            nft_mint,
+            payout_pool,
+            buyer_payout_claim: pda::payout_claim(&payout_pool, &buyer).0,
+            buyer_usdc: payment.account(&buyer),
+            creator_usdc: payment.account(&creator),
+            treasury_usdc: payment.account(&treasury),
+            proceeds_escrow,
+            proceeds_vault,
+            usdc_mint: payment.unless_sol(payment.mint),
+            buyer_nft: nft_account(&buyer, &nft_mint),
+            metadata: pda::metadata(&nft_mint).0,
+            master_edition: pda::master_edition(&nft_mint).0,
+            token_program: token_2022::ID,
+            payment_token_program: payment.token_program,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+        },
+        instruction::BuyListing {
+            amount,
+            expected_price,
+            max_total_cost,
+            proof,
+        },
+    )
+}
+
+/// `escrow_nft` is created on first use and must also sign the transaction
+pub fn list_for_resale(
+    seller: Pubkey,
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    escrow_nft: Pubkey,
+    price: u64,
+    amount: u64,
+    expires_at: Option<i64>,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    build(
+        accounts::ListForResale {
+            seller,
+            royalty_listing,
+            resale_listing: pda::resale_listing(&royalty_listing, &seller).0,
+            payout_pool,
+            seller_payout_claim: pda::payout_claim(&payout_pool, &seller).0,
+            seller_nft: nft_account(&seller, &nft_mint),
+            escrow_nft,
+            nft_mint,
+            token_program: token_2022::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::ListForResale {
+            price,
+            amount,
+            expires_at,
+        },
+    )
+}
+
+/// The purchase fails with `PriceMismatch` unless the lot still costs
+/// `expected_price` and that stays within `max_total_cost`
+#[allow(clippy::too_many_arguments)]
+pub fn buy_resale(
+    buyer: Pubkey,
+    seller: Pubkey,
+    creator: Pubkey,
+    treasury: Pubkey,
+    nft_mint: Pubkey,
+    escrow_nft: Pubkey,
+    payment: PaymentMint,
+    expected_price: u64,
+    max_total_cost: u64,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    build(
+        accounts::BuyResale {
+            buyer,
+            seller,
+            creator,
+            platform_config: pda::platform_config().0,
+            treasury,
+            royalty_listing,
+            resale_listing: pda::resale_listing(&royalty_listing, &seller).0,
+            payout_pool,
+            seller_payout_claim: pda::payout_claim(&payout_pool, &seller).0,
+            buyer_payout_claim: pda::payout_claim(&payout_pool, &buyer).0,
+            escrow_nft,
+            buyer_nft: nft_account(&buyer, &nft_mint),
+            nft_mint,
+            buyer_usdc: payment.account(&buyer),
+            seller_usdc: payment.account(&seller),
+            creator_usdc: payment.account(&creator),
+            treasury_usdc: payment.account(&treasury),
+            usdc_mint: payment.unless_sol(payment.mint),
+            token_program: token_2022::ID,
+            payment_token_program: payment.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::BuyResale {
+            expected_price,
+            max_total_cost,
+        },
+    )
+}
+
+pub fn update_resale_price(seller: Pubkey, creator: Pubkey, nft_mint: Pubkey, price: u64) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::UpdateResalePrice {
+            seller,
+            royalty_listing,
+            resale_listing: pda::resale_listing(&royalty_listing, &seller).0,
+        },
+        instruction::UpdateResalePrice { price },
+    )
+}
+
+pub fn cancel_resale(
+    seller: Pubkey,
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    escrow_nft: Pubkey,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::CancelResale {
+            seller,
+            royalty_listing,
+            resale_listing: pda::resale_listing(&royalty_listing, &seller).0,
+            escrow_nft,
+            seller_nft: nft_account(&seller, &nft_mint),
+            nft_mint,
+            token_program: token_2022::ID,
+        },
+        instruction::CancelResale {},
+    )
+}
+
+/// Permissionless; `payer` funds the seller's NFT account if it no longer exists
+pub fn close_expired_resale(
+    payer: Pubkey,
+    seller: Pubkey,
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    escrow_nft: Pubkey,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::CloseExpiredResale {
+            payer,
+            seller,
+            royalty_listing,
+            resale_listing: pda::resale_listing(&royalty_listing, &seller).0,
+            escrow_nft,
+            seller_nft: nft_account(&seller, &nft_mint),
+            nft_mint,
+            token_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CloseExpiredResale {},
+    )
+}
+
+/// `offer_vault` is created by the instruction and must also sign the
+/// transaction (unused for SOL listings)
+pub fn make_offer(
+    bidder: Pubkey,
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    offer_vault: Pubkey,
+    payment: PaymentMint,
+    amount: u64,
+    price: u64,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::MakeOffer {
+            bidder,
+            royalty_listing,
+            offer: pda::offer(&royalty_listing, &bidder).0,
+            bidder_usdc: payment.account(&bidder),
+            offer_vault: payment.unless_sol(offer_vault),
+            usdc_mint: payment.unless_sol(payment.mint),
+            token_program: payment.token_program,
+            system_program: system_program::ID,
+        },
+        instruction::MakeOffer { amount, price },
+    )
+}
+
+pub fn cancel_offer(
+    bidder: Pubkey,
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    offer_vault: Pubkey,
+    payment: PaymentMint,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::CancelOffer {
+            bidder,
+            royalty_listing,
+            offer: pda::offer(&royalty_listing, &bidder).0,
+            offer_vault: payment.unless_sol(offer_vault),
+            bidder_usdc: payment.account(&bidder),
+            usdc_mint: payment.unless_sol(payment.mint),
+            token_program: payment.token_program,
+        },
+        instruction::CancelOffer {},
+    )
+}
+
+/// The sale fails with `PriceMismatch` unless the bid still offers
+/// `expected_price` and the seller receives at least `min_proceeds` after fees
+#[allow(clippy::too_many_arguments)]
+pub fn accept_offer(
+    seller: Pubkey,
+    bidder: Pubkey,
+    creator: Pubkey,
+    treasury: Pubkey,
+    nft_mint: Pubkey,
+    offer_vault: Pubkey,
+    payment: PaymentMint,
+    expected_price: u64,
+    min_proceeds: u64,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    build(
+        accounts::AcceptOffer {
+            seller,
+            bidder,
+            creator,
+            platform_config: pda::platform_config().0,
+            treasury,
+            royalty_listing,
+            offer: pda::offer(&royalty_listing, &bidder).0,
+            offer_vault: payment.unless_sol(offer_vault),
+            payout_pool,
+            seller_payout_claim: pda::payout_claim(&payout_pool, &seller).0,
+            bidder_payout_claim: pda::payout_claim(&payout_pool, &bidder).0,
+            seller_nft: nft_account(&seller, &nft_mint),
+            bidder_nft: nft_account(&bidder, &nft_mint),
+            nft_mint,
+            seller_usdc: payment.account(&seller),
+            creator_usdc: payment.account(&creator),
+            treasury_usdc: payment.account(&treasury),
+            usdc_mint: payment.unless_sol(payment.mint),
+            token_program: token_2022::ID,
+            payment_token_program: payment.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::AcceptOffer {
+            expected_price,
+            min_proceeds,
+        },
+    )
+}
+
+/// `pool_vault` is created on first use and must also sign the transaction
+/// (unused for SOL listings). `escrowed_proceeds` must be set for listings
+/// whose proceeds unlock in payout tranches.
+pub fn deposit_payout(
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    pool_vault: Pubkey,
+    payment: PaymentMint,
+    amount: u64,
+    escrowed_proceeds: bool,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::DepositPayout {
+            creator,
+            royalty_listing,
+            payout_pool: pda::payout_pool(&royalty_listing).0,
+            proceeds_escrow: escrowed_proceeds.then(|| pda::proceeds_escrow(&royalty_listing).0),
+            creator_usdc: payment.account(&creator),
+            pool_vault: payment.unless_sol(pool_vault),
+            usdc_mint: payment.unless_sol(payment.mint),
+            token_program: payment.token_program,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::DepositPayout { amount },
+    )
+}
+
+pub fn claim_payout(
+    holder: Pubkey,
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    pool_vault: Pubkey,
+    payment: PaymentMint,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    build(
+        accounts::ClaimPayout {
+            holder,
+            royalty_listing,
+            payout_pool,
+            payout_claim: pda::payout_claim(&payout_pool, &holder).0,
+            holder_nft: nft_account(&holder, &nft_mint),
+            resale_listing: pda::resale_listing(&royalty_listing, &holder).0,
+            pool_vault: payment.unless_sol(pool_vault),
+            holder_usdc: payment.account(&holder),
+            usdc_mint: payment.unless_sol(payment.mint),
+            token_program: payment.token_program,
+        },
+        instruction::ClaimPayout {},
+    )
+}
+
+pub fn sync_payout_claim(
+    payer: Pubkey,
+    holder: Pubkey,
+    creator: Pubkey,
+    nft_mint: Pubkey,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    build(
+        accounts::SyncPayoutClaim {
+            payer,
+            holder,
+            royalty_listing,
+            payout_pool,
+            payout_claim: pda::payout_claim(&payout_pool, &holder).0,
+            holder_nft: nft_account(&holder, &nft_mint),
+            resale_listing: pda::resale_listing(&royalty_listing, &holder).0,
+            system_program: system_program::ID,
+        },
+        instruction::SyncPayoutClaim {},
+    )
+}
+
+pub fn release_proceeds(creator: Pubkey, nft_mint: Pubkey, payment: PaymentMint) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let proceeds_escrow = pda::proceeds_escrow(&royalty_listing).0;
+    build(
+        accounts::ReleaseProceeds {
+            creator,
+            royalty_listing,
+            proceeds_escrow,
+            proceeds_vault: payment.account(&proceeds_escrow),
+            creator_usdc: payment.account(&creator),
+            usdc_mint: payment.unless_sol(payment.mint),
+            token_program: payment.token_program,
+        },
+        instruction::ReleaseProceeds {},
+    )
+}
+
+/// `pool_vault` is created if the creator never deposited and must also sign
+/// the transaction (unused for SOL listings)
+pub fn forfeit_proceeds(
+    payer: Pubkey,
+    creator: Pubkey,
+    nft_mint: Pubkey,
+    pool_vault: Pubkey,
+    payment: PaymentMint,
+) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let proceeds_escrow = pda::proceeds_escrow(&royalty_listing).0;
+    build(
+        accounts::ForfeitProceeds {
+            payer,
+            royalty_listing,
+            payout_pool: pda::payout_pool(&royalty_listing).0,
+            proceeds_escrow,
+            proceeds_vault: payment.account(&proceeds_escrow),
+            pool_vault: payment.unless_sol(pool_vault),
+            usdc_mint: payment.unless_sol(payment.mint),
+            token_program: payment.token_program,
+            system_program: system_program::ID,
+        },
+        instruction::ForfeitProceeds {},
+    )
+}
+
+pub fn mark_delinquent(creator: Pubkey, nft_mint: Pubkey) -> Instruction {
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    build(
+        accounts::MarkDelinquent {
+            royalty_listing,
+            payout_pool: pda::payout_pool(&royalty_listing).0,
+        },
+        instruction::MarkDelinquent {},
+    )
+}
@@ -0,0 +1,12 @@
+//! Rust client for the royalties program.
+//!
+//! PDA derivation, instruction builders and account deserializers built on the
+//! program's own seeds, instruction data and account types, so off-chain callers
+//! stay in lockstep with on-chain layouts.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod presale;
+
+pub use royalties::ID as PROGRAM_ID;
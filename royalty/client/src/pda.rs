@@ -0,0 +1,71 @@
+//! PDA derivation for every program-owned account, plus the Metaplex accounts
+//! of royalty NFTs.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::metadata::mpl_token_metadata::accounts::{MasterEdition, Metadata};
+use royalties::state::{
+    AUCTION_SEED, OFFER_SEED, PAYOUT_CLAIM_SEED, PAYOUT_POOL_SEED, PLATFORM_CONFIG_SEED,
+    PROCEEDS_ESCROW_SEED, RESALE_LISTING_SEED, ROYALTY_LISTING_SEED,
+};
+
+/// Global `PlatformConfig`
+pub fn platform_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PLATFORM_CONFIG_SEED], &royalties::ID)
+}
+
+/// `RoyaltyListing` for a creator's share mint
+pub fn royalty_listing(creator: &Pubkey, nft_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ROYALTY_LISTING_SEED, creator.as_ref(), nft_mint.as_ref()],
+        &royalties::ID,
+    )
+}
+
+/// `ResaleListing` posted by a seller against a royalty listing
+pub fn resale_listing(royalty_listing: &Pubkey, seller: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[RESALE_LISTING_SEED, royalty_listing.as_ref(), seller.as_ref()],
+        &royalties::ID,
+    )
+}
+
+/// `Auction` bid state of an English auction listing
+pub fn auction(royalty_listing: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_SEED, royalty_listing.as_ref()], &royalties::ID)
+}
+
+/// `ProceedsEscrow` holding a listing's escrowed sale proceeds
+pub fn proceeds_escrow(royalty_listing: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROCEEDS_ESCROW_SEED, royalty_listing.as_ref()], &royalties::ID)
+}
+
+/// `Offer` posted by a bidder against a royalty listing
+pub fn offer(royalty_listing: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[OFFER_SEED, royalty_listing.as_ref(), bidder.as_ref()],
+        &royalties::ID,
+    )
+}
+
+/// `PayoutPool` of a royalty listing
+pub fn payout_pool(royalty_listing: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PAYOUT_POOL_SEED, royalty_listing.as_ref()], &royalties::ID)
+}
+
+/// `PayoutClaim` position of a holder in a payout pool
+pub fn payout_claim(payout_pool: &Pubkey, holder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYOUT_CLAIM_SEED, payout_pool.as_ref(), holder.as_ref()],
+        &royalties::ID,
+    )
+}
+
+/// Metaplex metadata account of a royalty NFT mint
+pub fn metadata(nft_mint: &Pubkey) -> (Pubkey, u8) {
+    Metadata::find_pda(nft_mint)
+}
+
+/// Metaplex master edition of a single-share royalty NFT mint
+pub fn master_edition(nft_mint: &Pubkey) -> (Pubkey, u8) {
+    MasterEdition::find_pda(nft_mint)
+}
@@ -0,0 +1,43 @@
+//! Merkle allowlists for listing presales.
+//!
+//! Builds the root passed to `create_listing` and the per-buyer proofs passed
+//! to `buy_listing`, hashing exactly as the program verifies them.
+
+use anchor_lang::prelude::Pubkey;
+use royalties::state::{presale_leaf, presale_node};
+
+/// Merkle root over the allowlisted wallets
+pub fn merkle_root(wallets: &[Pubkey]) -> [u8; 32] {
+    let mut level: Vec<[u8; 32]> = wallets.iter().map(presale_leaf).collect();
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level.first().copied().unwrap_or_default()
+}
+
+/// Proof that `wallet` is in the allowlist, or None if it isn't
+pub fn merkle_proof(wallets: &[Pubkey], wallet: &Pubkey) -> Option<Vec<[u8; 32]>> {
+    let mut index = wallets.iter().position(|listed| listed == wallet)?;
+    let mut level: Vec<[u8; 32]> = wallets.iter().map(presale_leaf).collect();
+    let mut proof = Vec::new();
+    while level.len() > 1 {
+        // The last node of an odd level has no sibling and moves up unchanged
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        level = parent_level(&level);
+        index /= 2;
+    }
+    Some(proof)
+}
+
+fn parent_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => presale_node(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
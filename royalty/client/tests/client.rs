@@ -0,0 +1,472 @@
+use anchor_lang::prelude::{pubkey, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{AccountSerialize, AnchorDeserialize, Discriminator};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::{token, token_2022};
+use royalties::instructions::CreateListingArgs;
+use royalties::state::{
+    ListingStatus, PayoutClaim, PlatformConfig, Presale, ProceedsRelease, RoyaltyListing, SaleMode,
+    AUCTION_SEED, MAX_PAYMENT_MINTS, OFFER_SEED, PAYOUT_CLAIM_SEED, PAYOUT_POOL_SEED,
+    PLATFORM_CONFIG_SEED, PROCEEDS_ESCROW_SEED, RESALE_LISTING_SEED, ROYALTY_LISTING_SEED,
+};
+use royalties_client::instructions::PaymentMint;
+use royalties_client::{accounts, instructions, pda, presale};
+
+fn meta(ix: &Instruction, key: &Pubkey) -> (bool, bool) {
+    let meta = ix
+        .accounts
+        .iter()
+        .find(|meta| meta.pubkey == *key)
+        .unwrap_or_else(|| panic!("{key} missing from instruction"));
+    (meta.is_signer, meta.is_writable)
+}
+
+#[test]
+fn pdas_match_deployed_addresses() {
+    // Pinned so a changed seed or program id shows up as a test failure
+    let creator = Pubkey::new_from_array([1; 32]);
+    let nft_mint = Pubkey::new_from_array([2; 32]);
+    let holder = Pubkey::new_from_array([3; 32]);
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+
+    assert_eq!(pda::platform_config().0, pubkey!("LgmEdWstBNNgzvSUKE6y5DKYFUUyY45UDj51PJckbgU"));
+    assert_eq!(royalty_listing, pubkey!("8BUEvwDKhhioh12EQvTtsXEQP4zYZuDqTeSSwAUmKUjm"));
+    assert_eq!(
+        pda::resale_listing(&royalty_listing, &holder).0,
+        pubkey!("Bqg7xyN66wzVf3PdJbVPKnxv9vyA3WZzZ67n1AMn2VK1")
+    );
+    assert_eq!(
+        pda::offer(&royalty_listing, &holder).0,
+        pubkey!("FCVGbfiQAs4JGAKPZpAyC6QieRxs1Jc3aLWKm7khwv4k")
+    );
+    assert_eq!(
+        pda::auction(&royalty_listing).0,
+        pubkey!("7r1S2MfKaGfDK9Q6KzMjp5suyTnKceX3918sByKrXLKb")
+    );
+    assert_eq!(
+        pda::proceeds_escrow(&royalty_listing).0,
+        pubkey!("GMiA7LANTuFCv5rsxvomj21vs6MXsdjJkoZicG5NP5fW")
+    );
+    assert_eq!(payout_pool, pubkey!("zgmugdasWByk8EGev3s7K424Qm3tHjJk8x27NPHb1Pp"));
+    assert_eq!(
+        pda::payout_claim(&payout_pool, &holder).0,
+        pubkey!("HreDxhfk89hyfERu5FHnf888XmEjwFKcDQYRPgYMFLLK")
+    );
+}
+
+#[test]
+fn pdas_derive_from_program_seeds() {
+    let creator = Pubkey::new_unique();
+    let nft_mint = Pubkey::new_unique();
+    let holder = Pubkey::new_unique();
+
+    assert_eq!(
+        pda::platform_config(),
+        Pubkey::find_program_address(&[PLATFORM_CONFIG_SEED], &royalties::ID)
+    );
+
+    let (royalty_listing, _) = pda::royalty_listing(&creator, &nft_mint);
+    assert_eq!(
+        royalty_listing,
+        Pubkey::find_program_address(
+            &[ROYALTY_LISTING_SEED, creator.as_ref(), nft_mint.as_ref()],
+            &royalties::ID
+        )
+        .0
+    );
+
+    assert_eq!(
+        pda::resale_listing(&royalty_listing, &holder).0,
+        Pubkey::find_program_address(
+            &[RESALE_LISTING_SEED, royalty_listing.as_ref(), holder.as_ref()],
+            &royalties::ID
+        )
+        .0
+    );
+
+    assert_eq!(
+        pda::offer(&royalty_listing, &holder).0,
+        Pubkey::find_program_address(
+            &[OFFER_SEED, royalty_listing.as_ref(), holder.as_ref()],
+            &royalties::ID
+        )
+        .0
+    );
+
+    assert_eq!(
+        pda::auction(&royalty_listing).0,
+        Pubkey::find_program_address(&[AUCTION_SEED, royalty_listing.as_ref()], &royalties::ID).0
+    );
+
+    assert_eq!(
+        pda::proceeds_escrow(&royalty_listing).0,
+        Pubkey::find_program_address(
+            &[PROCEEDS_ESCROW_SEED, royalty_listing.as_ref()],
+            &royalties::ID
+        )
+        .0
+    );
+
+    let (payout_pool, _) = pda::payout_pool(&royalty_listing);
+    assert_eq!(
+        payout_pool,
+        Pubkey::find_program_address(&[PAYOUT_POOL_SEED, royalty_listing.as_ref()], &royalties::ID)
+            .0
+    );
+
+    assert_eq!(
+        pda::payout_claim(&payout_pool, &holder).0,
+        Pubkey::find_program_address(
+            &[PAYOUT_CLAIM_SEED, payout_pool.as_ref(), holder.as_ref()],
+            &royalties::ID
+        )
+        .0
+    );
+}
+
+#[test]
+fn create_listing_encodes_args_and_accounts() {
+    let creator = Pubkey::new_unique();
+    let nft_mint = Pubkey::new_unique();
+    let payment_mint = Pubkey::new_unique();
+    let ix = instructions::create_listing(
+        creator,
+        nft_mint,
+        None,
+        CreateListingArgs {
+            metadata_uri: "ipfs://listing".to_string(),
+            percentage_bps: 500,
+            duration_seconds: 0,
+            payment_mint,
+            price: 1_000_000,
+            sale_mode: SaleMode::FixedPrice,
+            presale: None,
+            proceeds_release: ProceedsRelease::Immediate,
+            payout_interval_seconds: 0,
+            min_payout_per_period: 0,
+            total_shares: 10,
+            resale_allowed: true,
+            creator_royalty_bps: 250,
+        },
+    );
+
+    assert_eq!(ix.program_id, royalties::ID);
+    let discriminator = royalties::instruction::CreateListing::DISCRIMINATOR;
+    assert_eq!(&ix.data[..discriminator.len()], discriminator);
+    let args = CreateListingArgs::try_from_slice(&ix.data[discriminator.len()..]).unwrap();
+    assert_eq!(args.total_shares, 10);
+    assert_eq!(args.metadata_uri, "ipfs://listing");
+    assert_eq!(args.payment_mint, payment_mint);
+
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    assert_eq!(meta(&ix, &creator), (true, true));
+    assert_eq!(meta(&ix, &nft_mint), (true, true));
+    assert_eq!(meta(&ix, &royalty_listing), (false, true));
+    assert_eq!(meta(&ix, &pda::payout_pool(&royalty_listing).0), (false, true));
+    assert_eq!(meta(&ix, &pda::platform_config().0), (false, false));
+    // Metaplex metadata is created alongside the mint
+    let metadata = Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), nft_mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+    .0;
+    assert_eq!(pda::metadata(&nft_mint).0, metadata);
+    assert_eq!(meta(&ix, &metadata), (false, true));
+    assert_eq!(meta(&ix, &mpl_token_metadata::ID), (false, false));
+
+    // Joining the platform collection adds its mint, metadata and master edition
+    let collection_mint = Pubkey::new_unique();
+    let ix = instructions::create_listing(creator, nft_mint, Some(collection_mint), args);
+    assert_eq!(meta(&ix, &collection_mint), (false, false));
+    assert_eq!(meta(&ix, &pda::metadata(&collection_mint).0), (false, true));
+    assert_eq!(meta(&ix, &pda::master_edition(&collection_mint).0), (false, false));
+}
+
+#[test]
+fn buy_listing_derives_every_pda() {
+    let buyer = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let nft_mint = Pubkey::new_unique();
+    let usdc_mint = Pubkey::new_unique();
+    let payment = PaymentMint::token_2022(usdc_mint);
+    let proof = vec![[7; 32]];
+    let ix = instructions::buy_listing(
+        buyer, creator, treasury, nft_mint, payment, 3, 5, 15, proof, true,
+    );
+
+    let discriminator = royalties::instruction::BuyListing::DISCRIMINATOR;
+    assert_eq!(&ix.data[..discriminator.len()], discriminator);
+    let args =
+        <(u64, u64, u64, Vec<[u8; 32]>)>::try_from_slice(&ix.data[discriminator.len()..]).unwrap();
+    assert_eq!(args, (3, 5, 15, vec![[7; 32]]));
+
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    assert_eq!(meta(&ix, &buyer), (true, true));
+    assert_eq!(meta(&ix, &pda::platform_config().0), (false, true));
+    assert_eq!(meta(&ix, &royalty_listing), (false, true));
+    assert_eq!(meta(&ix, &payout_pool), (false, true));
+    assert_eq!(meta(&ix, &pda::payout_claim(&payout_pool, &buyer).0), (false, true));
+    assert_eq!(meta(&ix, &instructions::nft_account(&buyer, &nft_mint)), (false, true));
+    assert_eq!(
+        meta(
+            &ix,
+            &get_associated_token_address_with_program_id(&treasury, &usdc_mint, &token_2022::ID)
+        ),
+        (false, true)
+    );
+    assert_eq!(meta(&ix, &usdc_mint), (false, false));
+    // Escrowed proceeds go to the escrow's vault
+    let proceeds_escrow = pda::proceeds_escrow(&royalty_listing).0;
+    assert_eq!(meta(&ix, &proceeds_escrow), (false, true));
+    assert_eq!(
+        meta(
+            &ix,
+            &get_associated_token_address_with_program_id(
+                &proceeds_escrow,
+                &usdc_mint,
+                &token_2022::ID
+            )
+        ),
+        (false, true)
+    );
+    // Royalty NFT and payment mint both under Token-2022
+    assert_eq!(meta(&ix, &token_2022::ID), (false, false));
+    assert!(ix.accounts.iter().all(|meta| meta.pubkey != token::ID));
+    assert_eq!(meta(&ix, &pda::metadata(&nft_mint).0), (false, true));
+    assert_eq!(meta(&ix, &pda::master_edition(&nft_mint).0), (false, true));
+}
+
+#[test]
+fn sol_listings_omit_payment_token_accounts() {
+    let buyer = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let nft_mint = Pubkey::new_unique();
+    let pool_vault = Pubkey::new_unique();
+    let sol = PaymentMint::sol();
+
+    // Omitted optional accounts are passed as the program id
+    let placeholders = |ix: &Instruction| {
+        ix.accounts
+            .iter()
+            .filter(|meta| meta.pubkey == royalties::ID)
+            .count()
+    };
+    let ix =
+        instructions::buy_listing(buyer, creator, treasury, nft_mint, sol, 1, 5, 5, vec![], false);
+    assert_eq!(placeholders(&ix), 6);
+    assert!(ix.accounts.iter().all(|meta| meta.pubkey != native_mint::ID));
+    let ix = instructions::deposit_payout(creator, nft_mint, pool_vault, sol, 1, false);
+    assert_eq!(placeholders(&ix), 4);
+    assert!(ix.accounts.iter().all(|meta| meta.pubkey != pool_vault));
+    let ix = instructions::claim_payout(buyer, creator, nft_mint, pool_vault, sol);
+    assert_eq!(placeholders(&ix), 3);
+}
+
+#[test]
+fn buy_resale_moves_positions_between_seller_and_buyer() {
+    let buyer = Pubkey::new_unique();
+    let seller = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let nft_mint = Pubkey::new_unique();
+    let escrow_nft = Pubkey::new_unique();
+    let ix = instructions::buy_resale(
+        buyer,
+        seller,
+        creator,
+        Pubkey::new_unique(),
+        nft_mint,
+        escrow_nft,
+        PaymentMint::token(Pubkey::new_unique()),
+        7,
+        7,
+    );
+
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    assert_eq!(meta(&ix, &pda::resale_listing(&royalty_listing, &seller).0), (false, true));
+    assert_eq!(meta(&ix, &pda::payout_claim(&payout_pool, &seller).0), (false, true));
+    assert_eq!(meta(&ix, &pda::payout_claim(&payout_pool, &buyer).0), (false, true));
+    assert_eq!(meta(&ix, &escrow_nft), (false, true));
+}
+
+#[test]
+fn accept_offer_moves_positions_from_seller_to_bidder() {
+    let seller = Pubkey::new_unique();
+    let bidder = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let nft_mint = Pubkey::new_unique();
+    let offer_vault = Pubkey::new_unique();
+    let ix = instructions::accept_offer(
+        seller,
+        bidder,
+        creator,
+        Pubkey::new_unique(),
+        nft_mint,
+        offer_vault,
+        PaymentMint::token(Pubkey::new_unique()),
+        2_000_000,
+        1_850_000,
+    );
+
+    let discriminator = royalties::instruction::AcceptOffer::DISCRIMINATOR;
+    assert_eq!(&ix.data[..discriminator.len()], discriminator);
+    let args = <(u64, u64)>::try_from_slice(&ix.data[discriminator.len()..]).unwrap();
+    assert_eq!(args, (2_000_000, 1_850_000));
+
+    let royalty_listing = pda::royalty_listing(&creator, &nft_mint).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    assert_eq!(meta(&ix, &seller), (true, true));
+    assert_eq!(meta(&ix, &pda::offer(&royalty_listing, &bidder).0), (false, true));
+    assert_eq!(meta(&ix, &pda::payout_claim(&payout_pool, &seller).0), (false, true));
+    assert_eq!(meta(&ix, &pda::payout_claim(&payout_pool, &bidder).0), (false, true));
+    assert_eq!(meta(&ix, &offer_vault), (false, true));
+
+    // SOL bids are held by the offer itself
+    let ix = instructions::accept_offer(
+        seller,
+        bidder,
+        creator,
+        Pubkey::new_unique(),
+        nft_mint,
+        offer_vault,
+        PaymentMint::sol(),
+        2_000_000,
+        1_850_000,
+    );
+    assert!(!ix.accounts.iter().any(|meta| meta.pubkey == offer_vault));
+}
+
+#[test]
+fn claim_payout_uses_holder_position() {
+    let holder = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let nft_mint = Pubkey::new_unique();
+    let pool_vault = Pubkey::new_unique();
+    let payment = PaymentMint::token(Pubkey::new_unique());
+    let ix = instructions::claim_payout(holder, creator, nft_mint, pool_vault, payment);
+
+    assert_eq!(
+        ix.data,
+        royalties::instruction::ClaimPayout::DISCRIMINATOR.to_vec()
+    );
+    let payout_pool = pda::payout_pool(&pda::royalty_listing(&creator, &nft_mint).0).0;
+    assert_eq!(meta(&ix, &holder), (true, true));
+    assert_eq!(meta(&ix, &pda::payout_claim(&payout_pool, &holder).0), (false, true));
+    assert_eq!(meta(&ix, &pool_vault), (false, true));
+}
+
+#[test]
+fn accounts_round_trip_through_deserializers() {
+    let config = PlatformConfig {
+        authority: Pubkey::new_unique(),
+        pending_authority: Some(Pubkey::new_unique()),
+        treasury: Pubkey::new_unique(),
+        platform_fee_bps: 500,
+        secondary_fee_bps: 250,
+        total_fees_collected: 42,
+        payment_mints: vec![Pubkey::new_unique(); MAX_PAYMENT_MINTS],
+        collection_mint: Some(Pubkey::new_unique()),
+        bump: 254,
+    };
+    let mut data = Vec::new();
+    config.try_serialize(&mut data).unwrap();
+    // Fully populated config fills the allocation exactly
+    assert_eq!(data.len(), PlatformConfig::LEN);
+    let decoded = accounts::platform_config(&data).unwrap();
+    assert_eq!(decoded.authority, config.authority);
+    assert_eq!(decoded.pending_authority, config.pending_authority);
+    assert_eq!(decoded.total_fees_collected, 42);
+    assert_eq!(decoded.payment_mints, config.payment_mints);
+    assert_eq!(decoded.collection_mint, config.collection_mint);
+
+    let listing = RoyaltyListing {
+        creator: Pubkey::new_unique(),
+        nft_mint: Pubkey::new_unique(),
+        payment_mint: Pubkey::new_unique(),
+        metadata_uri: "ipfs://listing".to_string(),
+        percentage_bps: 500,
+        duration_seconds: 86_400,
+        start_timestamp: 1_700_000_000,
+        price: 1_000_000,
+        sale_mode: SaleMode::EnglishAuction {
+            reserve_price: 5_000_000,
+            end_time: 1_700_086_000,
+        },
+        presale: Some(Presale {
+            merkle_root: [9; 32],
+            ends_at: 1_700_003_600,
+        }),
+        proceeds_release: ProceedsRelease::PayoutTranches {
+            count: 4,
+            interval: 2_592_000,
+            min_deposit: 1_000_000,
+        },
+        total_shares: 10,
+        shares_sold: 4,
+        resale_allowed: true,
+        creator_royalty_bps: 250,
+        status: ListingStatus::Active,
+        delinquent: true,
+        bump: 253,
+    };
+    let mut data = Vec::new();
+    listing.try_serialize(&mut data).unwrap();
+    assert!(data.len() <= RoyaltyListing::LEN);
+    let decoded = accounts::royalty_listing(&data).unwrap();
+    assert_eq!(decoded.metadata_uri, listing.metadata_uri);
+    assert_eq!(decoded.shares_sold, 4);
+    assert!(decoded.sale_mode == listing.sale_mode);
+    assert!(decoded.presale == listing.presale);
+    assert!(decoded.proceeds_release == listing.proceeds_release);
+    assert!(decoded.status == ListingStatus::Active);
+    assert!(decoded.delinquent);
+
+    // Discriminator mismatch is rejected
+    assert!(accounts::payout_claim(&data).is_err());
+}
+
+#[test]
+fn payout_claim_round_trips() {
+    let claim = PayoutClaim {
+        payout_pool: Pubkey::new_unique(),
+        holder: Pubkey::new_unique(),
+        shares: 7,
+        reward_per_share_paid: 123_456_789_000_000,
+        pending: 11,
+        amount_claimed: 99,
+        claimed_at: 1_700_000_000,
+        bump: 250,
+    };
+    let mut data = Vec::new();
+    claim.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), PayoutClaim::LEN);
+
+    let decoded = accounts::payout_claim(&data).unwrap();
+    assert_eq!(decoded.shares, 7);
+    assert_eq!(decoded.reward_per_share_paid, claim.reward_per_share_paid);
+    assert_eq!(decoded.amount_claimed, 99);
+}
+
+#[test]
+fn presale_proofs_verify_against_the_root() {
+    let wallets: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+    let presale = Presale { merkle_root: presale::merkle_root(&wallets), ends_at: 0 };
+
+    for wallet in &wallets {
+        let proof = presale::merkle_proof(&wallets, wallet).unwrap();
+        assert!(presale.is_allowlisted(wallet, &proof));
+    }
+
+    // A listed wallet's proof doesn't admit anyone else
+    let outsider = Pubkey::new_unique();
+    assert!(presale::merkle_proof(&wallets, &outsider).is_none());
+    let proof = presale::merkle_proof(&wallets, &wallets[0]).unwrap();
+    assert!(!presale.is_allowlisted(&outsider, &proof));
+    assert!(!presale.is_allowlisted(&wallets[1], &proof));
+}
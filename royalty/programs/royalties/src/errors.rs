@@ -29,7 +29,7 @@ pub enum RoyaltiesError {
     #[msg("Payout pool is empty")]
     PayoutPoolEmpty,
     
-    #[msg("Already claimed for this period")]
+    #[msg("Nothing left to claim")]
     AlreadyClaimed,
     
     #[msg("Unauthorized")]
@@ -40,5 +40,104 @@ pub enum RoyaltiesError {
     
     #[msg("Calculation overflow")]
     Overflow,
+
+    #[msg("Listing has not reached the end of its term")]
+    ListingNotExpired,
+
+    #[msg("Invalid share amount")]
+    InvalidShareAmount,
+
+    #[msg("Not enough shares remaining")]
+    InsufficientShares,
+
+    #[msg("Shares of this listing have already been sold")]
+    SharesAlreadySold,
+
+    #[msg("Token account does not use the listing's payment mint")]
+    InvalidPaymentMint,
+
+    #[msg("Payment mint is not on the platform allowlist")]
+    PaymentMintNotAllowed,
+
+    #[msg("Payment mint is already on the platform allowlist")]
+    PaymentMintAlreadyAllowed,
+
+    #[msg("Payment mint allowlist is full")]
+    PaymentMintAllowlistFull,
+
+    #[msg("Payment token account required for this listing")]
+    PaymentAccountMissing,
+
+    #[msg("Platform collection has already been created")]
+    CollectionAlreadySet,
+
+    #[msg("Collection accounts do not match the platform collection")]
+    InvalidCollection,
+
+    #[msg("Price differs from the expected price or breaks the caller's cost or proceeds limit")]
+    PriceMismatch,
+
+    #[msg("Resale listing has expired")]
+    ResaleExpired,
+
+    #[msg("Resale listing has not expired")]
+    ResaleNotExpired,
+
+    #[msg("Resale expiry must be in the future")]
+    InvalidResaleExpiry,
+
+    #[msg("Auction timing must fall within the listing term")]
+    InvalidAuction,
+
+    #[msg("Listing is not an English auction")]
+    NotAnAuction,
+
+    #[msg("Listing is sold by auction")]
+    SoldByAuction,
+
+    #[msg("Auction has ended")]
+    AuctionEnded,
+
+    #[msg("Auction has not ended")]
+    AuctionNotEnded,
+
+    #[msg("Bid must meet the reserve price and beat the highest bid")]
+    BidTooLow,
+
+    #[msg("Auction already has bids")]
+    AuctionHasBids,
+
+    #[msg("Auction has not started")]
+    AuctionNotStarted,
+
+    #[msg("Presale must end in the future, within the listing term, on a buy_listing sale")]
+    InvalidPresale,
+
+    #[msg("Buyer is not on the presale allowlist")]
+    NotOnPresaleList,
+
+    #[msg("Proceeds release terms or proceeds escrow account are invalid")]
+    InvalidProceedsRelease,
+
+    #[msg("No escrowed proceeds are unlocked for release")]
+    NoProceedsToRelease,
+
+    #[msg("Listing has no scheduled payout past due")]
+    PayoutNotOverdue,
+
+    #[msg("Listing is already marked delinquent")]
+    AlreadyDelinquent,
+
+    #[msg("No shares have been sold")]
+    NoSharesSold,
+
+    #[msg("Escrowed proceeds are not forfeitable")]
+    ProceedsNotForfeitable,
+
+    #[msg("A payout schedule needs a non-zero minimum payout per period")]
+    InvalidPayoutSchedule,
+
+    #[msg("Dutch auction decay period must be non-zero")]
+    InvalidDecayPeriod,
 }
 
@@ -0,0 +1,359 @@
+use anchor_lang::prelude::*;
+use crate::state::{Presale, ProceedsRelease, SaleMode};
+
+/// Platform configuration created
+#[event]
+pub struct PlatformInitialized {
+    pub platform_config: Pubkey,
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub platform_fee_bps: u16,
+    pub secondary_fee_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Platform fees or treasury changed
+#[event]
+pub struct ConfigUpdated {
+    pub platform_config: Pubkey,
+    pub authority: Pubkey,
+    pub platform_fee_bps: u16,
+    pub secondary_fee_bps: u16,
+    pub treasury: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Platform authority transfer proposed (None = cleared)
+#[event]
+pub struct AuthorityProposed {
+    pub platform_config: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// Platform authority transfer accepted
+#[event]
+pub struct AuthorityTransferred {
+    pub platform_config: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Payment mint added to the platform allowlist
+#[event]
+pub struct PaymentMintAdded {
+    pub platform_config: Pubkey,
+    pub payment_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Payment mint removed from the platform allowlist
+#[event]
+pub struct PaymentMintRemoved {
+    pub platform_config: Pubkey,
+    pub payment_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Platform collection NFT created
+#[event]
+pub struct CollectionCreated {
+    pub platform_config: Pubkey,
+    pub collection_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Royalty listing created on the primary market
+#[event]
+pub struct ListingCreated {
+    pub royalty_listing: Pubkey,
+    pub creator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub percentage_bps: u16,
+    pub duration_seconds: u64,
+    pub price: u64,
+    pub total_shares: u64,
+    pub resale_allowed: bool,
+    pub creator_royalty_bps: u16,
+    pub timestamp: i64,
+    pub payment_mint: Pubkey,
+    pub sale_mode: SaleMode,
+    pub presale: Option<Presale>,
+    pub proceeds_release: ProceedsRelease,
+    pub payout_interval_seconds: u64,
+    pub min_payout_per_period: u64,
+}
+
+/// Creator changed the terms of an active listing
+#[event]
+pub struct ListingUpdated {
+    pub royalty_listing: Pubkey,
+    pub creator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub old_price: u64,
+    pub new_price: u64,
+    pub old_resale_allowed: bool,
+    pub new_resale_allowed: bool,
+    pub old_creator_royalty_bps: u16,
+    pub new_creator_royalty_bps: u16,
+    pub old_metadata_uri: String,
+    pub new_metadata_uri: String,
+    pub timestamp: i64,
+}
+
+/// Unsold royalty listing cancelled by its creator
+#[event]
+pub struct ListingCancelled {
+    pub royalty_listing: Pubkey,
+    pub creator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Creator ended the primary sale early; the shares sold make up the listing
+#[event]
+pub struct PrimarySaleClosed {
+    pub royalty_listing: Pubkey,
+    pub creator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub shares_sold: u64,
+    /// Shares that will never be minted
+    pub unsold_shares: u64,
+    pub timestamp: i64,
+}
+
+/// Royalty listing reached the end of its term
+#[event]
+pub struct ListingExpired {
+    pub royalty_listing: Pubkey,
+    pub creator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub expired_at: i64,
+    pub timestamp: i64,
+}
+
+/// Shares bought on the primary market
+#[event]
+pub struct ListingPurchased {
+    pub royalty_listing: Pubkey,
+    pub buyer: Pubkey,
+    pub creator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub total_price: u64,
+    pub platform_fee: u64,
+    pub creator_amount: u64,
+    pub shares_sold: u64,
+    pub timestamp: i64,
+}
+
+/// New highest bid escrowed on an English auction, refunding the one it beat
+#[event]
+pub struct BidPlaced {
+    pub auction: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub previous_bidder: Option<Pubkey>,
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+/// English auction settled: shares minted to the winner and proceeds split,
+/// or the winning bid refunded when the listing term ended first
+#[event]
+pub struct AuctionSettled {
+    pub auction: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub platform_fee: u64,
+    pub creator_amount: u64,
+    pub refunded: bool,
+    pub timestamp: i64,
+}
+
+/// Shares escrowed for sale on the secondary market
+#[event]
+pub struct ResaleListed {
+    pub resale_listing: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub timestamp: i64,
+    pub expires_at: Option<i64>,
+}
+
+/// Seller repriced a resale listing
+#[event]
+pub struct ResalePriceUpdated {
+    pub resale_listing: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub seller: Pubkey,
+    pub old_price: u64,
+    pub new_price: u64,
+    pub timestamp: i64,
+}
+
+/// Secondary market sale settled
+#[event]
+pub struct ResaleFilled {
+    pub resale_listing: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub platform_fee: u64,
+    pub creator_royalty: u64,
+    pub seller_amount: u64,
+    pub timestamp: i64,
+    /// Whether the creator is behind on scheduled payouts
+    pub delinquent: bool,
+}
+
+/// Expired resale listing closed and its shares returned to the seller
+#[event]
+pub struct ResaleExpired {
+    pub resale_listing: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub expired_at: i64,
+    pub timestamp: i64,
+}
+
+/// Secondary market listing withdrawn by its seller
+#[event]
+pub struct ResaleCancelled {
+    pub resale_listing: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Buyer escrowed a bid for shares
+#[event]
+pub struct OfferMade {
+    pub offer: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub bidder: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+/// Bid withdrawn and its escrow refunded to the bidder
+#[event]
+pub struct OfferCancelled {
+    pub offer: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+/// Holder sold shares into a bid
+#[event]
+pub struct OfferAccepted {
+    pub offer: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub seller: Pubkey,
+    pub bidder: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub platform_fee: u64,
+    pub creator_royalty: u64,
+    pub seller_amount: u64,
+    pub timestamp: i64,
+    /// Whether the creator is behind on scheduled payouts
+    pub delinquent: bool,
+}
+
+/// Creator deposited payouts for holders
+#[event]
+pub struct PayoutDeposited {
+    pub payout_pool: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub total_deposited: u64,
+    pub reward_per_share: u128,
+    pub timestamp: i64,
+    /// Whether the deposit unlocked a tranche of escrowed proceeds
+    pub tranche_unlocked: bool,
+    /// Deadline for the next scheduled payout (0 = no schedule)
+    pub next_payout_due: i64,
+    pub delinquent: bool,
+}
+
+/// Scheduled payout missed and listing flagged delinquent
+#[event]
+pub struct ListingDelinquent {
+    pub royalty_listing: Pubkey,
+    pub payout_pool: Pubkey,
+    pub creator: Pubkey,
+    pub due_at: i64,
+    pub timestamp: i64,
+}
+
+/// Holder withdrew payouts
+#[event]
+pub struct PayoutClaimed {
+    pub payout_pool: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub amount_claimed: u64,
+    pub timestamp: i64,
+}
+
+/// Unlocked escrowed proceeds paid to the creator
+#[event]
+pub struct ProceedsReleased {
+    pub proceeds_escrow: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub total_released: u64,
+    pub total_escrowed: u64,
+    pub timestamp: i64,
+}
+
+/// Locked escrowed proceeds paid out to holders after the creator stopped paying out
+#[event]
+pub struct ProceedsForfeited {
+    pub proceeds_escrow: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub payout_pool: Pubkey,
+    /// Amount the pool received
+    pub amount: u64,
+    pub total_escrowed: u64,
+    pub reward_per_share: u128,
+    pub timestamp: i64,
+}
+
+/// Payout position reconciled with the shares its holder holds
+#[event]
+pub struct PayoutClaimSynced {
+    pub payout_pool: Pubkey,
+    pub royalty_listing: Pubkey,
+    pub holder: Pubkey,
+    /// Shares credited after the sync
+    pub shares: u64,
+    /// Shares released because their tokens left the holder
+    pub released: u64,
+    /// Released shares taken up by the holder
+    pub taken: u64,
+    pub timestamp: i64,
+}
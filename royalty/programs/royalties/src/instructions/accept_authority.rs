@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::PlatformConfig;
+use crate::state::PLATFORM_CONFIG_SEED;
+use crate::events::AuthorityTransferred;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED],
+        bump = platform_config.bump,
+        constraint = platform_config.pending_authority == Some(new_authority.key()) @ RoyaltiesError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.platform_config;
+    let previous_authority = config.authority;
+    config.authority = ctx.accounts.new_authority.key();
+    config.pending_authority = None;
+
+    emit!(AuthorityTransferred {
+        platform_config: config.key(),
+        previous_authority,
+        new_authority: config.authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Mint, Token2022, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::state::{RoyaltyListing, Offer, PlatformConfig, ListingStatus, PayoutPool, PayoutClaim};
+use crate::state::{PLATFORM_CONFIG_SEED, ROYALTY_LISTING_SEED, OFFER_SEED, PAYOUT_POOL_SEED, PAYOUT_CLAIM_SEED};
+use crate::events::OfferAccepted;
+use crate::payments::{Escrow, ResaleSplit};
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    /// Holder selling shares into the bid
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Bidder receiving the shares and the offer rent
+    #[account(
+        mut,
+        constraint = bidder.key() != seller.key() @ RoyaltiesError::Unauthorized
+    )]
+    pub bidder: UncheckedAccount<'info>,
+
+    /// CHECK: Original creator receiving royalty
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// CHECK: Platform treasury
+    #[account(mut, address = platform_config.treasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [ROYALTY_LISTING_SEED, creator.key().as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = royalty_listing.status != ListingStatus::Expired @ RoyaltiesError::ListingExpired
+    )]
+    pub royalty_listing: Box<Account<'info, RoyaltyListing>>,
+
+    #[account(
+        mut,
+        seeds = [OFFER_SEED, royalty_listing.key().as_ref(), bidder.key().as_ref()],
+        bump = offer.bump,
+        constraint = offer.bidder == bidder.key() @ RoyaltiesError::Unauthorized,
+        close = bidder
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+
+    /// Escrow holding the bid (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = offer_vault.owner == offer.key() @ RoyaltiesError::Unauthorized,
+        constraint = offer_vault.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub offer_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump
+    )]
+    pub payout_pool: Box<Account<'info, PayoutPool>>,
+
+    /// Seller's position in the payout pool
+    #[account(
+        mut,
+        seeds = [PAYOUT_CLAIM_SEED, payout_pool.key().as_ref(), seller.key().as_ref()],
+        bump = seller_payout_claim.bump
+    )]
+    pub seller_payout_claim: Box<Account<'info, PayoutClaim>>,
+
+    /// Bidder's position in the payout pool
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = PayoutClaim::LEN,
+        seeds = [PAYOUT_CLAIM_SEED, payout_pool.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bidder_payout_claim: Box<Account<'info, PayoutClaim>>,
+
+    /// Seller's NFT account
+    #[account(
+        mut,
+        constraint = seller_nft.owner == seller.key() @ RoyaltiesError::NotOwner,
+        constraint = seller_nft.mint == royalty_listing.nft_mint @ RoyaltiesError::Unauthorized
+    )]
+    pub seller_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bidder's NFT account
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = nft_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
+        mint::token_program = token_program
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Seller's USDC (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = seller_usdc.owner == seller.key(),
+        constraint = seller_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub seller_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Creator's USDC for royalty (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = creator_usdc.owner == creator.key(),
+        constraint = creator_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub creator_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Treasury's USDC (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = treasury_usdc.owner == treasury.key(),
+        constraint = treasury_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub treasury_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Listing's payment mint (omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Royalty NFTs are always Token-2022 mints
+    pub token_program: Program<'info, Token2022>,
+    /// Token program of the payment mint (Token or Token-2022)
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// `expected_price` is the bid price the seller saw and `min_proceeds` is
+/// the least they accept after fees, so a bid replaced before the accept
+/// lands can't fill at a lower price.
+pub fn handler(ctx: Context<AcceptOffer>, expected_price: u64, min_proceeds: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        !ctx.accounts.royalty_listing.is_expired(clock.unix_timestamp),
+        RoyaltiesError::ListingExpired
+    );
+
+    let amount = ctx.accounts.offer.amount;
+    let price = ctx.accounts.offer.price;
+    require!(price == expected_price, RoyaltiesError::PriceMismatch);
+    // Shares must be held and credited to the seller's payout position
+    require!(
+        ctx.accounts.seller_nft.amount >= amount
+            && ctx.accounts.seller_payout_claim.shares >= amount,
+        RoyaltiesError::NotOwner
+    );
+
+    // Same split as buy_resale
+    let ResaleSplit {
+        platform_fee,
+        creator_royalty,
+        seller_amount,
+    } = ResaleSplit::new(
+        price,
+        ctx.accounts.platform_config.secondary_fee_bps,
+        ctx.accounts.royalty_listing.creator_royalty_bps,
+    )?;
+    require!(seller_amount >= min_proceeds, RoyaltiesError::PriceMismatch);
+
+    // Transfer shares from seller to bidder
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.seller_nft.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                to: ctx.accounts.bidder_nft.to_account_info(),
+                authority: ctx.accounts.seller.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.nft_mint.decimals,
+    )?;
+
+    // Pay seller, platform fee and creator royalty out of the bid escrow
+    let royalty_listing_key = ctx.accounts.royalty_listing.key();
+    let bidder_key = ctx.accounts.bidder.key();
+    let seeds = &[
+        OFFER_SEED,
+        royalty_listing_key.as_ref(),
+        bidder_key.as_ref(),
+        &[ctx.accounts.offer.bump],
+    ];
+    let escrow = Escrow {
+        sol: ctx.accounts.royalty_listing.pays_in_sol(),
+        authority: ctx.accounts.offer.to_account_info(),
+        signer_seeds: &[&seeds[..]],
+        vault: ctx.accounts.offer_vault.as_deref(),
+        mint: ctx.accounts.usdc_mint.as_deref(),
+        token_program: &ctx.accounts.payment_token_program,
+    };
+    escrow.pay(
+        ctx.accounts.seller.to_account_info(),
+        ctx.accounts.seller_usdc.as_deref(),
+        seller_amount,
+    )?;
+    // Transfer fees on the payment mint are borne by the recipients
+    let fee_received = escrow.pay(
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.treasury_usdc.as_deref(),
+        platform_fee,
+    )?;
+    escrow.pay(
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.creator_usdc.as_deref(),
+        creator_royalty,
+    )?;
+    // Anything sent to the vault beyond the bid goes to the seller
+    escrow.close_vault(
+        ctx.accounts.seller_usdc.as_deref(),
+        ctx.accounts.bidder.to_account_info(),
+    )?;
+
+    // Move payout rights for the shares from seller to bidder
+    let reward_per_share = ctx.accounts.payout_pool.reward_per_share;
+    ctx.accounts
+        .seller_payout_claim
+        .remove_shares(amount, reward_per_share)?;
+
+    let bidder_claim = &mut ctx.accounts.bidder_payout_claim;
+    bidder_claim.payout_pool = ctx.accounts.payout_pool.key();
+    bidder_claim.holder = bidder_key;
+    bidder_claim.add_shares(amount, reward_per_share)?;
+    bidder_claim.bump = ctx.bumps.bidder_payout_claim;
+
+    // Update platform stats
+    let config = &mut ctx.accounts.platform_config;
+    config.total_fees_collected = config
+        .total_fees_collected
+        .checked_add(fee_received)
+        .ok_or(RoyaltiesError::Overflow)?;
+
+    emit!(OfferAccepted {
+        offer: ctx.accounts.offer.key(),
+        royalty_listing: royalty_listing_key,
+        seller: ctx.accounts.seller.key(),
+        bidder: bidder_key,
+        nft_mint: ctx.accounts.offer.nft_mint,
+        amount,
+        price,
+        platform_fee,
+        creator_royalty,
+        seller_amount,
+        timestamp: clock.unix_timestamp,
+        delinquent: ctx.accounts.royalty_listing.delinquent,
+    });
+
+    Ok(())
+}
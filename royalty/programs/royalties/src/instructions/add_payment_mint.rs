@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::{PlatformConfig, MAX_PAYMENT_MINTS};
+use crate::state::PLATFORM_CONFIG_SEED;
+use crate::events::PaymentMintAdded;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct AddPaymentMint<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED],
+        bump = platform_config.bump,
+        has_one = authority @ RoyaltiesError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Mint to accept for listing prices and payouts
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+}
+
+pub fn handler(ctx: Context<AddPaymentMint>) -> Result<()> {
+    let config = &mut ctx.accounts.platform_config;
+    let payment_mint = ctx.accounts.payment_mint.key();
+
+    require!(
+        !config.is_payment_mint_allowed(&payment_mint),
+        RoyaltiesError::PaymentMintAlreadyAllowed
+    );
+    require!(
+        config.payment_mints.len() < MAX_PAYMENT_MINTS,
+        RoyaltiesError::PaymentMintAllowlistFull
+    );
+    config.payment_mints.push(payment_mint);
+
+    emit!(PaymentMintAdded {
+        platform_config: config.key(),
+        payment_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,7 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
+use anchor_spl::token_interface::{self, Mint, MintTo, Token2022, TokenAccount, TokenInterface};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{RoyaltyListing, ListingStatus, PlatformConfig};
+use anchor_spl::metadata::mpl_token_metadata::instructions::{
+    CreateMasterEditionV3Cpi, CreateMasterEditionV3CpiAccounts, CreateMasterEditionV3InstructionArgs,
+};
+use anchor_spl::metadata::Metadata;
+use crate::state::{RoyaltyListing, ListingStatus, SaleMode, ProceedsRelease, ProceedsEscrow, PlatformConfig, PayoutPool, PayoutClaim};
+use crate::state::{PLATFORM_CONFIG_SEED, ROYALTY_LISTING_SEED, PAYOUT_POOL_SEED, PAYOUT_CLAIM_SEED, PROCEEDS_ESCROW_SEED};
+use crate::events::ListingPurchased;
+use crate::payments::{Payment, PrimarySplit};
 use crate::errors::RoyaltiesError;
 
 #[derive(Accounts)]
@@ -15,7 +22,7 @@ pub struct BuyListing<'info> {
 
     #[account(
         mut,
-        seeds = [b"platform_config"],
+        seeds = [PLATFORM_CONFIG_SEED],
         bump = platform_config.bump
     )]
     pub platform_config: Account<'info, PlatformConfig>,
@@ -26,7 +33,7 @@ pub struct BuyListing<'info> {
 
     #[account(
         mut,
-        seeds = [b"royalty_listing", creator.key().as_ref(), nft_mint.key().as_ref()],
+        seeds = [ROYALTY_LISTING_SEED, creator.key().as_ref(), nft_mint.key().as_ref()],
         bump = royalty_listing.bump,
         constraint = royalty_listing.status == ListingStatus::Active @ RoyaltiesError::ListingNotActive,
         constraint = royalty_listing.creator == creator.key() @ RoyaltiesError::Unauthorized
@@ -35,104 +42,209 @@ pub struct BuyListing<'info> {
 
     #[account(
         mut,
-        constraint = nft_mint.key() == royalty_listing.nft_mint @ RoyaltiesError::Unauthorized
+        constraint = nft_mint.key() == royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
+        mint::token_program = token_program
     )]
-    pub nft_mint: Account<'info, Mint>,
+    pub nft_mint: InterfaceAccount<'info, Mint>,
 
-    /// Buyer's USDC token account
+    #[account(
+        mut,
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump
+    )]
+    pub payout_pool: Account<'info, PayoutPool>,
+
+    /// Buyer's position in the payout pool
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PayoutClaim::LEN,
+        seeds = [PAYOUT_CLAIM_SEED, payout_pool.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_payout_claim: Account<'info, PayoutClaim>,
+
+    /// Buyer's USDC token account (omitted for SOL listings)
     #[account(
         mut,
         constraint = buyer_usdc.owner == buyer.key(),
-        constraint = buyer_usdc.mint == usdc_mint.key()
+        constraint = buyer_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub buyer_usdc: Account<'info, TokenAccount>,
+    pub buyer_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Creator's USDC token account
+    /// Creator's USDC token account (omitted for SOL listings)
     #[account(
         mut,
         constraint = creator_usdc.owner == creator.key(),
-        constraint = creator_usdc.mint == usdc_mint.key()
+        constraint = creator_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub creator_usdc: Account<'info, TokenAccount>,
+    pub creator_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Treasury's USDC token account
+    /// Treasury's USDC token account (omitted for SOL listings)
     #[account(
         mut,
         constraint = treasury_usdc.owner == treasury.key(),
-        constraint = treasury_usdc.mint == usdc_mint.key()
+        constraint = treasury_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub treasury_usdc: Account<'info, TokenAccount>,
+    pub treasury_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// USDC mint
-    pub usdc_mint: Account<'info, Mint>,
+    /// Escrow receiving the creator's proceeds (omitted when they are paid immediately)
+    #[account(
+        mut,
+        seeds = [PROCEEDS_ESCROW_SEED, royalty_listing.key().as_ref()],
+        bump = proceeds_escrow.bump
+    )]
+    pub proceeds_escrow: Option<Box<Account<'info, ProceedsEscrow>>>,
 
-    /// Buyer's NFT token account (will receive the royalty NFT)
+    /// Proceeds escrow's USDC vault, created with the first sale (omitted for
+    /// SOL listings, whose escrow holds the lamports, and immediate proceeds)
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = proceeds_escrow,
+        associated_token::token_program = payment_token_program,
+    )]
+    pub proceeds_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Listing's payment mint (USDC or another allowlisted mint; omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Buyer's NFT token account (will receive the royalty shares)
     #[account(
         init_if_needed,
         payer = buyer,
         associated_token::mint = nft_mint,
         associated_token::authority = buyer,
+        associated_token::token_program = token_program,
+    )]
+    pub buyer_nft: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata account of the NFT mint
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
     )]
-    pub buyer_nft: Account<'info, TokenAccount>,
+    pub metadata: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Metaplex master edition, created when a single-share listing sells
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    /// Royalty NFTs are always Token-2022 mints
+    pub token_program: Program<'info, Token2022>,
+    /// Token program of the payment mint (Token or Token-2022)
+    pub payment_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<BuyListing>) -> Result<()> {
+/// `expected_price` is the per-share price the buyer saw and `max_total_cost`
+/// caps what they pay, so a repriced listing can't charge more than agreed.
+/// For Dutch auctions, whose price falls while the transaction lands,
+/// `expected_price` is the most the buyer pays per share.
+/// `proof` shows the buyer is allowlisted while a presale is open (empty otherwise).
+pub fn handler(
+    ctx: Context<BuyListing>,
+    amount: u64,
+    expected_price: u64,
+    max_total_cost: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
     let listing = &ctx.accounts.royalty_listing;
     let config = &ctx.accounts.platform_config;
 
-    // Calculate fee split
-    let total_price = listing.price;
-    let platform_fee = total_price
-        .checked_mul(config.platform_fee_bps as u64)
-        .ok_or(RoyaltiesError::Overflow)?
-        .checked_div(10000)
-        .ok_or(RoyaltiesError::Overflow)?;
-    let creator_amount = total_price
-        .checked_sub(platform_fee)
+    let clock = Clock::get()?;
+    require!(!listing.is_expired(clock.unix_timestamp), RoyaltiesError::ListingExpired);
+    let price = listing.current_price(clock.unix_timestamp)?;
+    if let Some(presale) = listing.presale.filter(|presale| presale.is_open(clock.unix_timestamp)) {
+        require!(
+            presale.is_allowlisted(&ctx.accounts.buyer.key(), &proof),
+            RoyaltiesError::NotOnPresaleList
+        );
+    }
+
+    require!(amount > 0, RoyaltiesError::InvalidShareAmount);
+    let shares_sold = listing
+        .shares_sold
+        .checked_add(amount)
         .ok_or(RoyaltiesError::Overflow)?;
+    require!(
+        shares_sold <= listing.total_shares,
+        RoyaltiesError::InsufficientShares
+    );
 
-    // Transfer USDC to creator
-    token::transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            token::Transfer {
-                from: ctx.accounts.buyer_usdc.to_account_info(),
-                to: ctx.accounts.creator_usdc.to_account_info(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            },
-        ),
+    // Calculate fee split
+    let total_price = price
+        .checked_mul(amount)
+        .ok_or(RoyaltiesError::Overflow)?;
+    let price_accepted = match listing.sale_mode {
+        SaleMode::DutchAuction { .. } => price <= expected_price,
+        _ => price == expected_price,
+    };
+    require!(
+        price_accepted && total_price <= max_total_cost,
+        RoyaltiesError::PriceMismatch
+    );
+    let PrimarySplit {
+        platform_fee,
         creator_amount,
-    )?;
+    } = PrimarySplit::new(total_price, config.platform_fee_bps)?;
 
-    // Transfer platform fee to treasury
-    token::transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            token::Transfer {
-                from: ctx.accounts.buyer_usdc.to_account_info(),
-                to: ctx.accounts.treasury_usdc.to_account_info(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            },
-        ),
+    // Pay the creator and the platform fee in the listing's payment mint
+    let payment = Payment {
+        sol: listing.pays_in_sol(),
+        payer: &ctx.accounts.buyer,
+        from: ctx.accounts.buyer_usdc.as_ref(),
+        mint: ctx.accounts.usdc_mint.as_ref(),
+        token_program: &ctx.accounts.payment_token_program,
+        system_program: &ctx.accounts.system_program,
+    };
+    // Escrowed proceeds go to the proceeds escrow instead of the creator
+    let creator_received = match listing.proceeds_release {
+        ProceedsRelease::Immediate => payment.pay(
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.creator_usdc.as_ref(),
+            creator_amount,
+        )?,
+        _ => payment.pay(
+            ctx.accounts
+                .proceeds_escrow
+                .as_ref()
+                .ok_or(RoyaltiesError::InvalidProceedsRelease)?
+                .to_account_info(),
+            ctx.accounts.proceeds_vault.as_deref(),
+            creator_amount,
+        )?,
+    };
+    // Transfer fees on the payment mint are borne by the recipients
+    let fee_received = payment.pay(
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.treasury_usdc.as_ref(),
         platform_fee,
     )?;
 
-    // Mint NFT to buyer
+    // Mint shares to buyer
     let creator_key = ctx.accounts.creator.key();
     let nft_mint_key = ctx.accounts.nft_mint.key();
     let seeds = &[
-        b"royalty_listing",
+        ROYALTY_LISTING_SEED,
         creator_key.as_ref(),
         nft_mint_key.as_ref(),
         &[listing.bump],
     ];
     let signer_seeds = &[&seeds[..]];
 
-    token::mint_to(
+    token_interface::mint_to(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             MintTo {
@@ -142,25 +254,73 @@ pub fn handler(ctx: Context<BuyListing>) -> Result<()> {
             },
             signer_seeds,
         ),
-        1, // Mint 1 NFT
+        amount,
     )?;
 
-    // Update listing status
+    // A single-share listing is a 1-of-1 NFT: lock its supply with a master edition.
+    // This hands mint and freeze authority to the edition PDA.
+    if listing.total_shares == 1 {
+        CreateMasterEditionV3Cpi::new(
+            &ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMasterEditionV3CpiAccounts {
+                edition: &ctx.accounts.master_edition.to_account_info(),
+                mint: &ctx.accounts.nft_mint.to_account_info(),
+                update_authority: &ctx.accounts.royalty_listing.to_account_info(),
+                mint_authority: &ctx.accounts.royalty_listing.to_account_info(),
+                payer: &ctx.accounts.buyer.to_account_info(),
+                metadata: &ctx.accounts.metadata.to_account_info(),
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                system_program: &ctx.accounts.system_program.to_account_info(),
+                rent: None,
+            },
+            CreateMasterEditionV3InstructionArgs {
+                max_supply: Some(0),
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+    }
+
+    // Credit the shares to the buyer's payout position
+    let reward_per_share = ctx.accounts.payout_pool.reward_per_share;
+    let buyer_claim = &mut ctx.accounts.buyer_payout_claim;
+    buyer_claim.payout_pool = ctx.accounts.payout_pool.key();
+    buyer_claim.holder = ctx.accounts.buyer.key();
+    buyer_claim.add_shares(amount, reward_per_share)?;
+    buyer_claim.bump = ctx.bumps.buyer_payout_claim;
+
+    // Payouts fall due from the first sale; the listing is Sold once every share is
     let listing = &mut ctx.accounts.royalty_listing;
-    listing.status = ListingStatus::Sold;
+    listing.shares_sold = shares_sold;
+    ctx.accounts.payout_pool.start_schedule(clock.unix_timestamp);
+    if listing.shares_sold == listing.total_shares {
+        listing.status = ListingStatus::Sold;
+    }
+    let sold_out = listing.status == ListingStatus::Sold;
+    let release = listing.proceeds_release;
+    if let Some(escrow) = ctx.accounts.proceeds_escrow.as_mut() {
+        escrow.record_sale(release, creator_received, sold_out, clock.unix_timestamp)?;
+    }
 
     // Update platform stats
     let config = &mut ctx.accounts.platform_config;
     config.total_fees_collected = config
         .total_fees_collected
-        .checked_add(platform_fee)
+        .checked_add(fee_received)
         .ok_or(RoyaltiesError::Overflow)?;
 
-    msg!(
-        "Purchase complete: {} USDC (fee: {} USDC)",
-        total_price as f64 / 1_000_000.0,
-        platform_fee as f64 / 1_000_000.0
-    );
+    let listing = &ctx.accounts.royalty_listing;
+    emit!(ListingPurchased {
+        royalty_listing: listing.key(),
+        buyer: ctx.accounts.buyer.key(),
+        creator: listing.creator,
+        nft_mint: listing.nft_mint,
+        amount,
+        total_price,
+        platform_fee,
+        creator_amount,
+        shares_sold: listing.shares_sold,
+        timestamp: clock.unix_timestamp,
+    });
 
     Ok(())
 }
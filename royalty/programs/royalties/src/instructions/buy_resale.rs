@@ -1,7 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{
+    self, Mint, Token2022, TokenAccount, TokenInterface, TransferChecked,
+};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{RoyaltyListing, ResaleListing, PlatformConfig};
+use crate::state::{RoyaltyListing, ResaleListing, PlatformConfig, ListingStatus, PayoutPool, PayoutClaim};
+use crate::state::{PLATFORM_CONFIG_SEED, ROYALTY_LISTING_SEED, RESALE_LISTING_SEED, PAYOUT_POOL_SEED, PAYOUT_CLAIM_SEED};
+use crate::events::ResaleFilled;
+use crate::payments::{Payment, ResaleSplit};
 use crate::errors::RoyaltiesError;
 
 #[derive(Accounts)]
@@ -10,7 +15,10 @@ pub struct BuyResale<'info> {
     pub buyer: Signer<'info>,
 
     /// CHECK: Seller receiving payment
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = seller.key() != buyer.key() @ RoyaltiesError::Unauthorized
+    )]
     pub seller: UncheckedAccount<'info>,
 
     /// CHECK: Original creator receiving royalty
@@ -19,7 +27,7 @@ pub struct BuyResale<'info> {
 
     #[account(
         mut,
-        seeds = [b"platform_config"],
+        seeds = [PLATFORM_CONFIG_SEED],
         bump = platform_config.bump
     )]
     pub platform_config: Account<'info, PlatformConfig>,
@@ -29,27 +37,52 @@ pub struct BuyResale<'info> {
     pub treasury: UncheckedAccount<'info>,
 
     #[account(
-        seeds = [b"royalty_listing", creator.key().as_ref(), royalty_listing.nft_mint.as_ref()],
-        bump = royalty_listing.bump
+        seeds = [ROYALTY_LISTING_SEED, creator.key().as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = royalty_listing.status != ListingStatus::Expired @ RoyaltiesError::ListingExpired
     )]
     pub royalty_listing: Account<'info, RoyaltyListing>,
 
     #[account(
         mut,
-        seeds = [b"resale_listing", royalty_listing.key().as_ref(), seller.key().as_ref()],
+        seeds = [RESALE_LISTING_SEED, royalty_listing.key().as_ref(), seller.key().as_ref()],
         bump = resale_listing.bump,
         constraint = resale_listing.seller == seller.key() @ RoyaltiesError::Unauthorized,
         close = seller
     )]
     pub resale_listing: Account<'info, ResaleListing>,
 
+    #[account(
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump
+    )]
+    pub payout_pool: Box<Account<'info, PayoutPool>>,
+
+    /// Seller's position in the payout pool
+    #[account(
+        mut,
+        seeds = [PAYOUT_CLAIM_SEED, payout_pool.key().as_ref(), seller.key().as_ref()],
+        bump = seller_payout_claim.bump
+    )]
+    pub seller_payout_claim: Box<Account<'info, PayoutClaim>>,
+
+    /// Buyer's position in the payout pool
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PayoutClaim::LEN,
+        seeds = [PAYOUT_CLAIM_SEED, payout_pool.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_payout_claim: Box<Account<'info, PayoutClaim>>,
+
     /// Escrow holding the NFT
     #[account(
         mut,
         constraint = escrow_nft.owner == resale_listing.key() @ RoyaltiesError::Unauthorized,
-        constraint = escrow_nft.amount == 1 @ RoyaltiesError::NotOwner
+        constraint = escrow_nft.amount >= resale_listing.amount @ RoyaltiesError::NotOwner
     )]
-    pub escrow_nft: Account<'info, TokenAccount>,
+    pub escrow_nft: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// Buyer's NFT account
     #[account(
@@ -57,149 +90,176 @@ pub struct BuyResale<'info> {
         payer = buyer,
         associated_token::mint = nft_mint,
         associated_token::authority = buyer,
+        associated_token::token_program = token_program,
     )]
-    pub buyer_nft: Account<'info, TokenAccount>,
+    pub buyer_nft: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// CHECK: NFT mint
-    pub nft_mint: UncheckedAccount<'info>,
+    #[account(
+        address = royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
+        mint::token_program = token_program
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// Buyer's USDC
+    /// Buyer's USDC (omitted for SOL listings)
     #[account(
         mut,
-        constraint = buyer_usdc.owner == buyer.key()
+        constraint = buyer_usdc.owner == buyer.key(),
+        constraint = buyer_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub buyer_usdc: Account<'info, TokenAccount>,
+    pub buyer_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    /// Seller's USDC
+    /// Seller's USDC (omitted for SOL listings)
     #[account(
         mut,
-        constraint = seller_usdc.owner == seller.key()
+        constraint = seller_usdc.owner == seller.key(),
+        constraint = seller_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub seller_usdc: Account<'info, TokenAccount>,
+    pub seller_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    /// Creator's USDC (for royalty)
+    /// Creator's USDC for royalty (omitted for SOL listings)
     #[account(
         mut,
-        constraint = creator_usdc.owner == creator.key()
+        constraint = creator_usdc.owner == creator.key(),
+        constraint = creator_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub creator_usdc: Account<'info, TokenAccount>,
+    pub creator_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    /// Treasury's USDC
+    /// Treasury's USDC (omitted for SOL listings)
     #[account(
         mut,
-        constraint = treasury_usdc.owner == treasury.key()
+        constraint = treasury_usdc.owner == treasury.key(),
+        constraint = treasury_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub treasury_usdc: Account<'info, TokenAccount>,
+    pub treasury_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Listing's payment mint (omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
-    pub token_program: Program<'info, Token>,
+    /// Royalty NFTs are always Token-2022 mints
+    pub token_program: Program<'info, Token2022>,
+    /// Token program of the payment mint (Token or Token-2022)
+    pub payment_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<BuyResale>) -> Result<()> {
+/// `expected_price` is the lot price the buyer saw and `max_total_cost` caps
+/// what they pay, so a repriced resale can't charge more than agreed.
+pub fn handler(ctx: Context<BuyResale>, expected_price: u64, max_total_cost: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        !ctx.accounts.royalty_listing.is_expired(clock.unix_timestamp),
+        RoyaltiesError::ListingExpired
+    );
+    require!(
+        !ctx.accounts.resale_listing.is_expired(clock.unix_timestamp),
+        RoyaltiesError::ResaleExpired
+    );
+
     // Get values early to reduce stack usage
     let total_price = ctx.accounts.resale_listing.price;
+    require!(
+        total_price == expected_price && total_price <= max_total_cost,
+        RoyaltiesError::PriceMismatch
+    );
     let secondary_fee_bps = ctx.accounts.platform_config.secondary_fee_bps;
     let creator_royalty_bps = ctx.accounts.royalty_listing.creator_royalty_bps;
 
     // Calculate fee splits
-    let platform_fee = total_price
-        .checked_mul(secondary_fee_bps as u64)
-        .ok_or(RoyaltiesError::Overflow)?
-        .checked_div(10000)
-        .ok_or(RoyaltiesError::Overflow)?;
-
-    let creator_royalty = total_price
-        .checked_mul(creator_royalty_bps as u64)
-        .ok_or(RoyaltiesError::Overflow)?
-        .checked_div(10000)
-        .ok_or(RoyaltiesError::Overflow)?;
-
-    let seller_amount = total_price
-        .checked_sub(platform_fee)
-        .ok_or(RoyaltiesError::Overflow)?
-        .checked_sub(creator_royalty)
-        .ok_or(RoyaltiesError::Overflow)?;
+    let ResaleSplit {
+        platform_fee,
+        creator_royalty,
+        seller_amount,
+    } = ResaleSplit::new(total_price, secondary_fee_bps, creator_royalty_bps)?;
 
-    // Transfer to seller
-    token::transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.buyer_usdc.to_account_info(),
-                to: ctx.accounts.seller_usdc.to_account_info(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            },
-        ),
+    // Pay seller, platform fee and creator royalty in the listing's payment mint
+    let payment = Payment {
+        sol: ctx.accounts.royalty_listing.pays_in_sol(),
+        payer: &ctx.accounts.buyer,
+        from: ctx.accounts.buyer_usdc.as_deref(),
+        mint: ctx.accounts.usdc_mint.as_deref(),
+        token_program: &ctx.accounts.payment_token_program,
+        system_program: &ctx.accounts.system_program,
+    };
+    payment.pay(
+        ctx.accounts.seller.to_account_info(),
+        ctx.accounts.seller_usdc.as_deref(),
         seller_amount,
     )?;
-
-    // Transfer platform fee
-    token::transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.buyer_usdc.to_account_info(),
-                to: ctx.accounts.treasury_usdc.to_account_info(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            },
-        ),
+    // Transfer fees on the payment mint are borne by the recipients
+    let fee_received = payment.pay(
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.treasury_usdc.as_deref(),
         platform_fee,
     )?;
+    payment.pay(
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.creator_usdc.as_deref(),
+        creator_royalty,
+    )?;
 
-    // Transfer creator royalty
-    if creator_royalty > 0 {
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.buyer_usdc.to_account_info(),
-                    to: ctx.accounts.creator_usdc.to_account_info(),
-                    authority: ctx.accounts.buyer.to_account_info(),
-                },
-            ),
-            creator_royalty,
-        )?;
-    }
-
-    // Transfer NFT from escrow to buyer
+    // Transfer shares from escrow to buyer
+    let resale_amount = ctx.accounts.resale_listing.amount;
     let resale_bump = ctx.accounts.resale_listing.bump;
     let royalty_listing_key = ctx.accounts.royalty_listing.key();
     let seller_key = ctx.accounts.seller.key();
     let seeds = &[
-        b"resale_listing",
+        RESALE_LISTING_SEED,
         royalty_listing_key.as_ref(),
         seller_key.as_ref(),
         &[resale_bump],
     ];
     let signer_seeds = &[&seeds[..]];
 
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.escrow_nft.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
                 to: ctx.accounts.buyer_nft.to_account_info(),
                 authority: ctx.accounts.resale_listing.to_account_info(),
             },
             signer_seeds,
         ),
-        1,
+        resale_amount,
+        ctx.accounts.nft_mint.decimals,
     )?;
 
+    // Move payout rights for the shares from seller to buyer
+    let reward_per_share = ctx.accounts.payout_pool.reward_per_share;
+    ctx.accounts
+        .seller_payout_claim
+        .remove_shares(resale_amount, reward_per_share)?;
+
+    let buyer_claim = &mut ctx.accounts.buyer_payout_claim;
+    buyer_claim.payout_pool = ctx.accounts.payout_pool.key();
+    buyer_claim.holder = ctx.accounts.buyer.key();
+    buyer_claim.add_shares(resale_amount, reward_per_share)?;
+    buyer_claim.bump = ctx.bumps.buyer_payout_claim;
+
     // Update platform stats
     let config = &mut ctx.accounts.platform_config;
     config.total_fees_collected = config
         .total_fees_collected
-        .checked_add(platform_fee)
+        .checked_add(fee_received)
         .ok_or(RoyaltiesError::Overflow)?;
 
-    msg!(
-        "Resale complete: {} USDC (platform: {}, creator: {})",
-        total_price as f64 / 1_000_000.0,
-        platform_fee as f64 / 1_000_000.0,
-        creator_royalty as f64 / 1_000_000.0
-    );
+    emit!(ResaleFilled {
+        resale_listing: ctx.accounts.resale_listing.key(),
+        royalty_listing: royalty_listing_key,
+        seller: seller_key,
+        buyer: ctx.accounts.buyer.key(),
+        nft_mint: ctx.accounts.resale_listing.nft_mint,
+        amount: resale_amount,
+        price: total_price,
+        platform_fee,
+        creator_royalty,
+        seller_amount,
+        timestamp: clock.unix_timestamp,
+        delinquent: ctx.accounts.royalty_listing.delinquent,
+    });
 
     Ok(())
 }
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, SetAuthority, Token2022};
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use crate::state::{RoyaltyListing, ListingStatus, SaleMode, Auction, PayoutPool};
+use crate::state::{ProceedsEscrow, ProceedsRelease};
+use crate::state::{ROYALTY_LISTING_SEED, PAYOUT_POOL_SEED, AUCTION_SEED, PROCEEDS_ESCROW_SEED};
+use crate::events::ListingCancelled;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ROYALTY_LISTING_SEED, creator.key().as_ref(), nft_mint.key().as_ref()],
+        bump = royalty_listing.bump,
+        constraint = matches!(
+            royalty_listing.status,
+            ListingStatus::Active | ListingStatus::Expired
+        ) @ RoyaltiesError::ListingNotActive,
+        constraint = royalty_listing.creator == creator.key() @ RoyaltiesError::Unauthorized,
+        constraint = royalty_listing.shares_sold == 0 @ RoyaltiesError::SharesAlreadySold,
+        close = creator
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    #[account(
+        mut,
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump,
+        close = creator
+    )]
+    pub payout_pool: Account<'info, PayoutPool>,
+
+    /// Bid state of an English auction listing (omitted for fixed-price listings)
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, royalty_listing.key().as_ref()],
+        bump = auction.bump,
+        close = creator
+    )]
+    pub auction: Option<Account<'info, Auction>>,
+
+    /// Escrow of the creator's proceeds (omitted for immediate proceeds)
+    #[account(
+        mut,
+        seeds = [PROCEEDS_ESCROW_SEED, royalty_listing.key().as_ref()],
+        bump = proceeds_escrow.bump,
+        close = creator
+    )]
+    pub proceeds_escrow: Option<Account<'info, ProceedsEscrow>>,
+
+    #[account(
+        mut,
+        constraint = nft_mint.key() == royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
+        mint::token_program = token_program
+    )]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+
+    /// Royalty NFTs are always Token-2022 mints
+    pub token_program: Program<'info, Token2022>,
+}
+
+pub fn handler(ctx: Context<CancelListing>) -> Result<()> {
+    // An auction can only be called off before anyone has escrowed a bid
+    if let SaleMode::EnglishAuction { .. } = ctx.accounts.royalty_listing.sale_mode {
+        let auction = ctx
+            .accounts
+            .auction
+            .as_ref()
+            .ok_or(RoyaltiesError::InvalidAuction)?;
+        require!(auction.highest_bidder.is_none(), RoyaltiesError::AuctionHasBids);
+    }
+    require!(
+        ctx.accounts.royalty_listing.proceeds_release == ProceedsRelease::Immediate
+            || ctx.accounts.proceeds_escrow.is_some(),
+        RoyaltiesError::InvalidProceedsRelease
+    );
+
+    let creator_key = ctx.accounts.creator.key();
+    let nft_mint_key = ctx.accounts.nft_mint.key();
+    let seeds = &[
+        ROYALTY_LISTING_SEED,
+        creator_key.as_ref(),
+        nft_mint_key.as_ref(),
+        &[ctx.accounts.royalty_listing.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Revoke mint and freeze authorities so the unsold shares can never be minted
+    for authority_type in [AuthorityType::MintTokens, AuthorityType::FreezeAccount] {
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: ctx.accounts.royalty_listing.to_account_info(),
+                    account_or_mint: ctx.accounts.nft_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            authority_type,
+            None,
+        )?;
+    }
+
+    // Listing, pool, auction and proceeds escrow accounts are closed on exit
+    // and their rent refunded to the creator; the event is the only record left
+    let listing = &ctx.accounts.royalty_listing;
+
+    emit!(ListingCancelled {
+        royalty_listing: listing.key(),
+        creator: listing.creator,
+        nft_mint: listing.nft_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{RoyaltyListing, Offer};
+use crate::state::{ROYALTY_LISTING_SEED, OFFER_SEED};
+use crate::events::OfferCancelled;
+use crate::payments::Escrow;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    /// Closing the offer refunds SOL bids along with the rent
+    #[account(
+        mut,
+        seeds = [OFFER_SEED, royalty_listing.key().as_ref(), bidder.key().as_ref()],
+        bump = offer.bump,
+        constraint = offer.bidder == bidder.key() @ RoyaltiesError::Unauthorized,
+        close = bidder
+    )]
+    pub offer: Account<'info, Offer>,
+
+    /// Escrow holding the bid (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = offer_vault.owner == offer.key() @ RoyaltiesError::Unauthorized,
+        constraint = offer_vault.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub offer_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bidder's USDC account to refund (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = bidder_usdc.owner == bidder.key(),
+        constraint = bidder_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub bidder_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Listing's payment mint (omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program of the payment mint (Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<CancelOffer>) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    let royalty_listing_key = ctx.accounts.royalty_listing.key();
+    let bidder_key = ctx.accounts.bidder.key();
+    let seeds = &[
+        OFFER_SEED,
+        royalty_listing_key.as_ref(),
+        bidder_key.as_ref(),
+        &[offer.bump],
+    ];
+
+    // Refund the whole vault; SOL bids come back when the offer is closed
+    Escrow {
+        sol: ctx.accounts.royalty_listing.pays_in_sol(),
+        authority: offer.to_account_info(),
+        signer_seeds: &[&seeds[..]],
+        vault: ctx.accounts.offer_vault.as_ref(),
+        mint: ctx.accounts.usdc_mint.as_ref(),
+        token_program: &ctx.accounts.token_program,
+    }
+    .close_vault(
+        ctx.accounts.bidder_usdc.as_ref(),
+        ctx.accounts.bidder.to_account_info(),
+    )?;
+
+    emit!(OfferCancelled {
+        offer: offer.key(),
+        royalty_listing: royalty_listing_key,
+        bidder: bidder_key,
+        amount: offer.amount,
+        price: offer.price,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
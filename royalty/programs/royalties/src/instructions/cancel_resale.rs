@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, Token2022, TokenAccount, TransferChecked};
 use crate::state::{RoyaltyListing, ResaleListing};
+use crate::state::{ROYALTY_LISTING_SEED, RESALE_LISTING_SEED};
+use crate::events::ResaleCancelled;
 use crate::errors::RoyaltiesError;
 
 #[derive(Accounts)]
@@ -9,14 +11,14 @@ pub struct CancelResale<'info> {
     pub seller: Signer<'info>,
 
     #[account(
-        seeds = [b"royalty_listing", royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
         bump = royalty_listing.bump
     )]
     pub royalty_listing: Account<'info, RoyaltyListing>,
 
     #[account(
         mut,
-        seeds = [b"resale_listing", royalty_listing.key().as_ref(), seller.key().as_ref()],
+        seeds = [RESALE_LISTING_SEED, royalty_listing.key().as_ref(), seller.key().as_ref()],
         bump = resale_listing.bump,
         constraint = resale_listing.seller == seller.key() @ RoyaltiesError::Unauthorized,
         close = seller
@@ -28,16 +30,23 @@ pub struct CancelResale<'info> {
         mut,
         constraint = escrow_nft.owner == resale_listing.key() @ RoyaltiesError::Unauthorized
     )]
-    pub escrow_nft: Account<'info, TokenAccount>,
+    pub escrow_nft: InterfaceAccount<'info, TokenAccount>,
 
     /// Seller's NFT account to receive back
     #[account(
         mut,
         constraint = seller_nft.owner == seller.key()
     )]
-    pub seller_nft: Account<'info, TokenAccount>,
+    pub seller_nft: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(
+        address = royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
+        mint::token_program = token_program
+    )]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+
+    /// Royalty NFTs are always Token-2022 mints
+    pub token_program: Program<'info, Token2022>,
 }
 
 pub fn handler(ctx: Context<CancelResale>) -> Result<()> {
@@ -45,29 +54,37 @@ pub fn handler(ctx: Context<CancelResale>) -> Result<()> {
     let royalty_listing_key = ctx.accounts.royalty_listing.key();
     let seller_key = ctx.accounts.seller.key();
 
-    // Transfer NFT back from escrow
+    // Transfer shares back from escrow
     let seeds = &[
-        b"resale_listing",
+        RESALE_LISTING_SEED,
         royalty_listing_key.as_ref(),
         seller_key.as_ref(),
         &[resale.bump],
     ];
     let signer_seeds = &[&seeds[..]];
 
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.escrow_nft.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
                 to: ctx.accounts.seller_nft.to_account_info(),
                 authority: ctx.accounts.resale_listing.to_account_info(),
             },
             signer_seeds,
         ),
-        1,
+        resale.amount,
+        ctx.accounts.nft_mint.decimals,
     )?;
 
-    msg!("Resale listing cancelled");
+    emit!(ResaleCancelled {
+        resale_listing: resale.key(),
+        royalty_listing: royalty_listing_key,
+        seller: seller_key,
+        amount: resale.amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
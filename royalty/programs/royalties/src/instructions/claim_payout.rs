@@ -1,6 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 use crate::state::{RoyaltyListing, PayoutPool, PayoutClaim};
+use crate::state::{ROYALTY_LISTING_SEED, RESALE_LISTING_SEED, PAYOUT_POOL_SEED, PAYOUT_CLAIM_SEED};
+use crate::events::PayoutClaimed;
+use crate::instructions::sync_payout_claim::held_shares;
+use crate::payments::withdraw_lamports;
 use crate::errors::RoyaltiesError;
 
 #[derive(Accounts)]
@@ -9,100 +15,144 @@ pub struct ClaimPayout<'info> {
     pub holder: Signer<'info>,
 
     #[account(
-        seeds = [b"royalty_listing", royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
         bump = royalty_listing.bump
     )]
     pub royalty_listing: Account<'info, RoyaltyListing>,
 
     #[account(
         mut,
-        seeds = [b"payout_pool", royalty_listing.key().as_ref()],
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
         bump = payout_pool.bump
     )]
     pub payout_pool: Account<'info, PayoutPool>,
 
+    /// Holder's position, reconciled against the shares they hold
     #[account(
-        init,
-        payer = holder,
-        space = PayoutClaim::LEN,
-        seeds = [
-            b"payout_claim",
-            payout_pool.key().as_ref(),
-            holder.key().as_ref(),
-            &payout_pool.period.to_le_bytes()
-        ],
-        bump
+        mut,
+        seeds = [PAYOUT_CLAIM_SEED, payout_pool.key().as_ref(), holder.key().as_ref()],
+        bump = payout_claim.bump,
+        constraint = payout_claim.holder == holder.key() @ RoyaltiesError::NotOwner
     )]
     pub payout_claim: Account<'info, PayoutClaim>,
 
-    /// Holder's NFT account - proves ownership
+    /// CHECK: holder's associated share account - proves ownership
+    #[account(
+        address = get_associated_token_address_with_program_id(
+            &holder.key(),
+            &royalty_listing.nft_mint,
+            &token_2022::ID,
+        ) @ RoyaltiesError::NotOwner
+    )]
+    pub holder_nft: UncheckedAccount<'info>,
+
+    /// CHECK: holder's resale listing PDA, counted if it exists
     #[account(
-        constraint = holder_nft.owner == holder.key() @ RoyaltiesError::NotOwner,
-        constraint = holder_nft.mint == royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
-        constraint = holder_nft.amount == 1 @ RoyaltiesError::NotOwner
+        seeds = [RESALE_LISTING_SEED, royalty_listing.key().as_ref(), holder.key().as_ref()],
+        bump
     )]
-    pub holder_nft: Account<'info, TokenAccount>,
+    pub resale_listing: UncheckedAccount<'info>,
 
-    /// Pool vault holding USDC
+    /// Pool vault holding USDC (omitted for SOL listings)
     #[account(
         mut,
-        constraint = pool_vault.owner == payout_pool.key()
+        constraint = pool_vault.owner == payout_pool.key(),
+        constraint = pool_vault.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub pool_vault: Account<'info, TokenAccount>,
+    pub pool_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Holder's USDC account to receive payout
+    /// Holder's USDC account to receive payout (omitted for SOL listings)
     #[account(
         mut,
-        constraint = holder_usdc.owner == holder.key()
+        constraint = holder_usdc.owner == holder.key(),
+        constraint = holder_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub holder_usdc: Account<'info, TokenAccount>,
+    pub holder_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    /// Listing's payment mint (omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program of the payment mint (Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<ClaimPayout>) -> Result<()> {
-    let pool = &ctx.accounts.payout_pool;
+    require!(
+        ctx.accounts.payout_pool.available()? > 0,
+        RoyaltiesError::PayoutPoolEmpty
+    );
 
-    // Check there's something to claim
-    let available = pool
-        .total_deposited
-        .checked_sub(pool.total_claimed)
-        .ok_or(RoyaltiesError::Overflow)?;
-    require!(available > 0, RoyaltiesError::PayoutPoolEmpty);
+    // Pay only on shares still held; shares transferred away are released
+    // to whoever holds them now
+    let held = held_shares(
+        &ctx.accounts.holder_nft.to_account_info(),
+        &ctx.accounts.resale_listing.to_account_info(),
+    )?;
+    let claim = &mut ctx.accounts.payout_claim;
+    claim.reconcile(held, &mut ctx.accounts.payout_pool)?;
 
-    // Calculate holder's share
-    // Since each NFT represents the full percentage, the holder gets the full available amount
-    // In a fractional system, this would be proportional to tokens held
-    let claim_amount = available;
+    // Settle everything deposited since the holder's last settlement
+    let pool = &ctx.accounts.payout_pool;
+    claim.settle(pool.reward_per_share)?;
+    let claim_amount = claim.withdraw()?;
+    require!(claim_amount > 0, RoyaltiesError::AlreadyClaimed);
+    require!(
+        claim_amount <= pool.available()?,
+        RoyaltiesError::InsufficientFunds
+    );
 
     let clock = Clock::get()?;
+    claim.claimed_at = clock.unix_timestamp;
 
-    // Transfer from pool vault to holder
     let royalty_listing_key = ctx.accounts.royalty_listing.key();
-    let seeds = &[
-        b"payout_pool",
-        royalty_listing_key.as_ref(),
-        &[pool.bump],
-    ];
-    let signer_seeds = &[&seeds[..]];
-
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.pool_vault.to_account_info(),
-                to: ctx.accounts.holder_usdc.to_account_info(),
-                authority: ctx.accounts.payout_pool.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        claim_amount,
-    )?;
-
-    // Get pool key before mutable borrow
-    let payout_pool_key = ctx.accounts.payout_pool.key();
-    let pool_period = ctx.accounts.payout_pool.period;
+    if ctx.accounts.royalty_listing.pays_in_sol() {
+        // SOL pools hold the deposited lamports themselves
+        withdraw_lamports(
+            &ctx.accounts.payout_pool.to_account_info(),
+            &ctx.accounts.holder.to_account_info(),
+            claim_amount,
+        )?;
+    } else {
+        // Transfer from pool vault to holder
+        let pool_vault = ctx
+            .accounts
+            .pool_vault
+            .as_ref()
+            .ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        let holder_usdc = ctx
+            .accounts
+            .holder_usdc
+            .as_ref()
+            .ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        let usdc_mint = ctx
+            .accounts
+            .usdc_mint
+            .as_ref()
+            .ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        let seeds = &[
+            PAYOUT_POOL_SEED,
+            royalty_listing_key.as_ref(),
+            &[pool.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // Any transfer fee is withheld from the holder's side
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: pool_vault.to_account_info(),
+                    mint: usdc_mint.to_account_info(),
+                    to: holder_usdc.to_account_info(),
+                    authority: ctx.accounts.payout_pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            claim_amount,
+            usdc_mint.decimals,
+        )?;
+    }
 
     // Update pool
     let pool = &mut ctx.accounts.payout_pool;
@@ -111,20 +161,14 @@ pub fn handler(ctx: Context<ClaimPayout>) -> Result<()> {
         .checked_add(claim_amount)
         .ok_or(RoyaltiesError::Overflow)?;
 
-    // Record claim
-    let claim = &mut ctx.accounts.payout_claim;
-    claim.payout_pool = payout_pool_key;
-    claim.holder = ctx.accounts.holder.key();
-    claim.amount_claimed = claim_amount;
-    claim.claimed_at = clock.unix_timestamp;
-    claim.bump = ctx.bumps.payout_claim;
-
-    msg!(
-        "Claimed {} USDC for period {}",
-        claim_amount as f64 / 1_000_000.0,
-        pool_period
-    );
+    emit!(PayoutClaimed {
+        payout_pool: pool.key(),
+        royalty_listing: royalty_listing_key,
+        holder: ctx.accounts.holder.key(),
+        amount: claim_amount,
+        amount_claimed: ctx.accounts.payout_claim.amount_claimed,
+        timestamp: clock.unix_timestamp,
+    });
 
     Ok(())
 }
-
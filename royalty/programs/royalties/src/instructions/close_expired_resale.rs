@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, Token2022, TokenAccount, TransferChecked,
+};
+use crate::state::{RoyaltyListing, ResaleListing};
+use crate::state::{ROYALTY_LISTING_SEED, RESALE_LISTING_SEED};
+use crate::events::ResaleExpired;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct CloseExpiredResale<'info> {
+    /// Anyone may close an expired ask; pays for the seller's ATA if it was closed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Seller receiving the shares and rent back
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    #[account(
+        mut,
+        seeds = [RESALE_LISTING_SEED, royalty_listing.key().as_ref(), seller.key().as_ref()],
+        bump = resale_listing.bump,
+        constraint = resale_listing.seller == seller.key() @ RoyaltiesError::Unauthorized,
+        close = seller
+    )]
+    pub resale_listing: Account<'info, ResaleListing>,
+
+    /// Escrow holding the NFT, closed to the seller once emptied
+    #[account(
+        mut,
+        constraint = escrow_nft.owner == resale_listing.key() @ RoyaltiesError::Unauthorized
+    )]
+    pub escrow_nft: InterfaceAccount<'info, TokenAccount>,
+
+    /// Seller's NFT account to receive back
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = seller,
+        associated_token::token_program = token_program,
+    )]
+    pub seller_nft: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        address = royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
+        mint::token_program = token_program
+    )]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+
+    /// Royalty NFTs are always Token-2022 mints
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CloseExpiredResale>) -> Result<()> {
+    let clock = Clock::get()?;
+    let resale = &ctx.accounts.resale_listing;
+    require!(resale.is_expired(clock.unix_timestamp), RoyaltiesError::ResaleNotExpired);
+
+    let royalty_listing_key = ctx.accounts.royalty_listing.key();
+    let seller_key = ctx.accounts.seller.key();
+    let seeds = &[
+        RESALE_LISTING_SEED,
+        royalty_listing_key.as_ref(),
+        seller_key.as_ref(),
+        &[resale.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Return everything in escrow so the account can be closed
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow_nft.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                to: ctx.accounts.seller_nft.to_account_info(),
+                authority: resale.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.escrow_nft.amount,
+        ctx.accounts.nft_mint.decimals,
+    )?;
+
+    // Escrow rent was paid by the seller when listing
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_nft.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: resale.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit!(ResaleExpired {
+        resale_listing: resale.key(),
+        royalty_listing: royalty_listing_key,
+        seller: seller_key,
+        amount: resale.amount,
+        expired_at: resale.expires_at.unwrap_or_default(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
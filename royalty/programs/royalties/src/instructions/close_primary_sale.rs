@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::state::{RoyaltyListing, ListingStatus, ProceedsEscrow, ProceedsRelease};
+use crate::state::{ROYALTY_LISTING_SEED, PROCEEDS_ESCROW_SEED};
+use crate::events::PrimarySaleClosed;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct ClosePrimarySale<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = royalty_listing.status == ListingStatus::Active @ RoyaltiesError::ListingNotActive,
+        constraint = royalty_listing.creator == creator.key() @ RoyaltiesError::Unauthorized,
+        constraint = royalty_listing.shares_sold > 0 @ RoyaltiesError::NoSharesSold
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    /// Escrow of the creator's proceeds (omitted for immediate proceeds)
+    #[account(
+        mut,
+        seeds = [PROCEEDS_ESCROW_SEED, royalty_listing.key().as_ref()],
+        bump = proceeds_escrow.bump
+    )]
+    pub proceeds_escrow: Option<Account<'info, ProceedsEscrow>>,
+}
+
+pub fn handler(ctx: Context<ClosePrimarySale>) -> Result<()> {
+    let clock = Clock::get()?;
+    let listing = &mut ctx.accounts.royalty_listing;
+    require!(
+        !listing.is_expired(clock.unix_timestamp),
+        RoyaltiesError::ListingExpired
+    );
+
+    // The shares sold so far become the whole listing, as if it had sold out.
+    // The payout schedule already runs from the first sale.
+    let unsold_shares = listing.total_shares - listing.shares_sold;
+    listing.total_shares = listing.shares_sold;
+    listing.status = ListingStatus::Sold;
+    let release = listing.proceeds_release;
+    if release != ProceedsRelease::Immediate {
+        let escrow = ctx
+            .accounts
+            .proceeds_escrow
+            .as_mut()
+            .ok_or(RoyaltiesError::InvalidProceedsRelease)?;
+        escrow.record_sale(release, 0, true, clock.unix_timestamp)?;
+    }
+
+    emit!(PrimarySaleClosed {
+        royalty_listing: listing.key(),
+        creator: listing.creator,
+        nft_mint: listing.nft_mint,
+        shares_sold: listing.shares_sold,
+        unsold_shares,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata::instructions::{
+    CreateMasterEditionV3Cpi, CreateMasterEditionV3CpiAccounts, CreateMasterEditionV3InstructionArgs,
+};
+use anchor_spl::metadata::mpl_token_metadata::types::{CollectionDetails, Creator, DataV2};
+use anchor_spl::metadata::{self, CreateMetadataAccountsV3, Metadata};
+use anchor_spl::token_interface::{self, Mint, MintTo, Token2022, TokenAccount};
+use crate::state::PlatformConfig;
+use crate::state::PLATFORM_CONFIG_SEED;
+use crate::events::CollectionCreated;
+use crate::errors::RoyaltiesError;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CreateCollectionArgs {
+    /// Collection name shown by wallets and marketplaces
+    pub name: String,
+    /// Collection symbol
+    pub symbol: String,
+    /// URI of the collection's off-chain metadata JSON
+    pub uri: String,
+}
+
+#[derive(Accounts)]
+pub struct CreateCollection<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED],
+        bump = platform_config.bump,
+        has_one = authority @ RoyaltiesError::Unauthorized,
+        constraint = platform_config.collection_mint.is_none() @ RoyaltiesError::CollectionAlreadySet
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = platform_config,
+        mint::freeze_authority = platform_config,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = platform_config,
+        extensions::metadata_pointer::metadata_address = collection_metadata,
+    )]
+    pub collection_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Metaplex metadata account of the collection mint, created by the Token Metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), collection_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition of the collection mint, created by the Token Metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), collection_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    /// Authority's token account holding the collection NFT
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = collection_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub authority_collection_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler(ctx: Context<CreateCollection>, args: CreateCollectionArgs) -> Result<()> {
+    require!(
+        !args.uri.is_empty() && args.uri.len() <= 200,
+        RoyaltiesError::InvalidMetadataUri
+    );
+
+    let seeds = &[PLATFORM_CONFIG_SEED, &[ctx.accounts.platform_config.bump]];
+    let signer_seeds = &[&seeds[..]];
+    let platform_config = ctx.accounts.platform_config.to_account_info();
+
+    // The collection NFT itself: a single token held by the authority
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.collection_mint.to_account_info(),
+                to: ctx.accounts.authority_collection_nft.to_account_info(),
+                authority: platform_config.clone(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+
+    // Sized collection metadata; the platform config PDA is its update authority,
+    // so the program can verify royalty NFTs into it
+    metadata::create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.collection_metadata.to_account_info(),
+                mint: ctx.accounts.collection_mint.to_account_info(),
+                mint_authority: platform_config.clone(),
+                payer: ctx.accounts.authority.to_account_info(),
+                update_authority: platform_config.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        DataV2 {
+            name: args.name,
+            symbol: args.symbol,
+            uri: args.uri,
+            seller_fee_basis_points: 0,
+            creators: Some(vec![Creator {
+                address: platform_config.key(),
+                verified: true,
+                share: 100,
+            }]),
+            collection: None,
+            uses: None,
+        },
+        true, // is_mutable
+        true, // update_authority_is_signer
+        Some(CollectionDetails::V1 { size: 0 }),
+    )?;
+
+    CreateMasterEditionV3Cpi::new(
+        &ctx.accounts.token_metadata_program.to_account_info(),
+        CreateMasterEditionV3CpiAccounts {
+            edition: &ctx.accounts.collection_master_edition.to_account_info(),
+            mint: &ctx.accounts.collection_mint.to_account_info(),
+            update_authority: &platform_config,
+            mint_authority: &platform_config,
+            payer: &ctx.accounts.authority.to_account_info(),
+            metadata: &ctx.accounts.collection_metadata.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            rent: None,
+        },
+        CreateMasterEditionV3InstructionArgs {
+            max_supply: Some(0),
+        },
+    )
+    .invoke_signed(signer_seeds)?;
+
+    let config = &mut ctx.accounts.platform_config;
+    config.collection_mint = Some(ctx.accounts.collection_mint.key());
+
+    emit!(CollectionCreated {
+        platform_config: config.key(),
+        collection_mint: ctx.accounts.collection_mint.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
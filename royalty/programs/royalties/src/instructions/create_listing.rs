@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
-use crate::state::{RoyaltyListing, ListingStatus, PlatformConfig};
+use anchor_spl::metadata::mpl_token_metadata::types::{Collection, Creator, DataV2};
+use anchor_spl::metadata::{self, CreateMetadataAccountsV3, Metadata, VerifySizedCollectionItem};
+use anchor_spl::token_interface::{Mint, Token2022};
+use crate::state::{RoyaltyListing, ListingStatus, SaleMode, Presale, ProceedsRelease, ProceedsEscrow, Auction, PlatformConfig, PayoutPool, ROYALTY_NFT_SYMBOL};
+use crate::state::{PLATFORM_CONFIG_SEED, ROYALTY_LISTING_SEED, PAYOUT_POOL_SEED, AUCTION_SEED, PROCEEDS_ESCROW_SEED};
+use crate::events::ListingCreated;
 use crate::errors::RoyaltiesError;
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -11,8 +15,24 @@ pub struct CreateListingArgs {
     pub percentage_bps: u16,
     /// Duration in seconds (0 = perpetual)
     pub duration_seconds: u64,
-    /// Price in USDC (6 decimals)
+    /// Mint buyers pay in and payouts are made in (must be allowlisted)
+    pub payment_mint: Pubkey,
+    /// Price per share in base units of the payment mint (fixed-price listings)
     pub price: u64,
+    /// Fixed price, English auction of the whole share supply, or Dutch auction
+    pub sale_mode: SaleMode,
+    /// Allowlist-only window before the listing opens to everyone
+    pub presale: Option<Presale>,
+    /// Pay proceeds to the creator at each sale, or escrow them until released
+    pub proceeds_release: ProceedsRelease,
+    /// Committed seconds between payouts once sold out, e.g. 2_592_000 for
+    /// monthly (0 = no schedule)
+    pub payout_interval_seconds: u64,
+    /// Least a deposit must pay holders to count as a scheduled payout
+    /// (required with a payout schedule)
+    pub min_payout_per_period: u64,
+    /// Number of fungible shares to issue (1 = single NFT)
+    pub total_shares: u64,
     /// Allow resale on secondary market
     pub resale_allowed: bool,
     /// Creator royalty on resales (basis points)
@@ -26,7 +46,7 @@ pub struct CreateListing<'info> {
     pub creator: Signer<'info>,
 
     #[account(
-        seeds = [b"platform_config"],
+        seeds = [PLATFORM_CONFIG_SEED],
         bump = platform_config.bump
     )]
     pub platform_config: Account<'info, PlatformConfig>,
@@ -35,21 +55,77 @@ pub struct CreateListing<'info> {
         init,
         payer = creator,
         space = RoyaltyListing::LEN,
-        seeds = [b"royalty_listing", creator.key().as_ref(), nft_mint.key().as_ref()],
+        seeds = [ROYALTY_LISTING_SEED, creator.key().as_ref(), nft_mint.key().as_ref()],
         bump
     )]
     pub royalty_listing: Account<'info, RoyaltyListing>,
 
+    #[account(
+        init,
+        payer = creator,
+        space = PayoutPool::LEN,
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump
+    )]
+    pub payout_pool: Account<'info, PayoutPool>,
+
+    /// Bid state of an English auction listing (omitted for fixed-price listings)
+    #[account(
+        init,
+        payer = creator,
+        space = Auction::LEN,
+        seeds = [AUCTION_SEED, royalty_listing.key().as_ref()],
+        bump
+    )]
+    pub auction: Option<Box<Account<'info, Auction>>>,
+
+    /// Escrow of the creator's proceeds (omitted when they are paid immediately)
+    #[account(
+        init,
+        payer = creator,
+        space = ProceedsEscrow::LEN,
+        seeds = [PROCEEDS_ESCROW_SEED, royalty_listing.key().as_ref()],
+        bump
+    )]
+    pub proceeds_escrow: Option<Box<Account<'info, ProceedsEscrow>>>,
+
     #[account(
         init,
         payer = creator,
         mint::decimals = 0,
         mint::authority = royalty_listing,
         mint::freeze_authority = royalty_listing,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = royalty_listing,
+        extensions::metadata_pointer::metadata_address = metadata,
     )]
-    pub nft_mint: Account<'info, Mint>,
+    pub nft_mint: InterfaceAccount<'info, Mint>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Metaplex metadata account of the NFT mint, created by the Token Metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Platform collection mint (required once the platform has a collection)
+    #[account(
+        constraint = platform_config.collection_mint == Some(collection_mint.key()) @ RoyaltiesError::InvalidCollection
+    )]
+    pub collection_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: Metaplex metadata of the collection, validated by the Token Metadata program
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition of the collection, validated by the Token Metadata program
+    pub collection_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// Royalty NFTs are always Token-2022 mints
+    pub token_program: Program<'info, Token2022>,
+    pub token_metadata_program: Program<'info, Metadata>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -60,36 +136,223 @@ pub fn handler(ctx: Context<CreateListing>, args: CreateListingArgs) -> Result<(
         args.percentage_bps > 0 && args.percentage_bps <= 10000,
         RoyaltiesError::InvalidPercentage
     );
-    require!(args.price > 0, RoyaltiesError::InvalidPrice);
-    require!(
-        args.metadata_uri.len() > 0 && args.metadata_uri.len() <= 200,
-        RoyaltiesError::InvalidMetadataUri
-    );
     require!(
-        args.creator_royalty_bps <= 1000, // Max 10% creator royalty on resales
-        RoyaltiesError::FeeTooHigh
+        ctx.accounts.platform_config.is_payment_mint_allowed(&args.payment_mint),
+        RoyaltiesError::PaymentMintNotAllowed
     );
+    require!(args.total_shares > 0, RoyaltiesError::InvalidShareAmount);
 
     let listing = &mut ctx.accounts.royalty_listing;
     let clock = Clock::get()?;
 
     listing.creator = ctx.accounts.creator.key();
     listing.nft_mint = ctx.accounts.nft_mint.key();
+    listing.payment_mint = args.payment_mint;
     listing.metadata_uri = args.metadata_uri;
     listing.percentage_bps = args.percentage_bps;
     listing.duration_seconds = args.duration_seconds;
     listing.start_timestamp = clock.unix_timestamp;
     listing.price = args.price;
+    listing.sale_mode = args.sale_mode;
+    listing.presale = args.presale;
+    listing.proceeds_release = args.proceeds_release;
+    listing.delinquent = false;
+    listing.total_shares = args.total_shares;
+    listing.shares_sold = 0;
     listing.resale_allowed = args.resale_allowed;
     listing.creator_royalty_bps = args.creator_royalty_bps;
     listing.status = ListingStatus::Active;
     listing.bump = ctx.bumps.royalty_listing;
+    listing.validate_terms()?;
+    // A dust deposit must not count as a scheduled payout
+    require!(
+        args.payout_interval_seconds == 0 || args.min_payout_per_period > 0,
+        RoyaltiesError::InvalidPayoutSchedule
+    );
 
-    msg!(
-        "Listing created: {}% for {} USDC",
-        args.percentage_bps as f64 / 100.0,
-        args.price as f64 / 1_000_000.0
+    // Sales must happen while the royalty term still runs
+    let term_ends = listing.start_timestamp.saturating_add(
+        i64::try_from(listing.duration_seconds).unwrap_or(i64::MAX),
     );
+    match listing.sale_mode {
+        SaleMode::FixedPrice => {
+            require!(ctx.accounts.auction.is_none(), RoyaltiesError::InvalidAuction);
+        }
+        SaleMode::DutchAuction { start_time, .. } => {
+            require!(ctx.accounts.auction.is_none(), RoyaltiesError::InvalidAuction);
+            require!(
+                listing.duration_seconds == 0 || start_time < term_ends,
+                RoyaltiesError::InvalidAuction
+            );
+        }
+        SaleMode::EnglishAuction { end_time, .. } => {
+            require!(
+                end_time > clock.unix_timestamp
+                    && (listing.duration_seconds == 0 || end_time < term_ends),
+                RoyaltiesError::InvalidAuction
+            );
+            let auction = ctx
+                .accounts
+                .auction
+                .as_mut()
+                .ok_or(RoyaltiesError::InvalidAuction)?;
+            auction.royalty_listing = listing.key();
+            auction.highest_bidder = None;
+            auction.highest_bid = 0;
+            auction.bid_count = 0;
+            auction.bump = ctx.bumps.auction.ok_or(RoyaltiesError::InvalidAuction)?;
+        }
+    }
+
+    // Bids aren't gated, so presales only apply to buy_listing sales
+    if let Some(presale) = listing.presale {
+        require!(
+            !matches!(listing.sale_mode, SaleMode::EnglishAuction { .. })
+                && presale.is_open(clock.unix_timestamp)
+                && (listing.duration_seconds == 0 || presale.ends_at < term_ends),
+            RoyaltiesError::InvalidPresale
+        );
+    }
+
+    if listing.proceeds_release == ProceedsRelease::Immediate {
+        require!(ctx.accounts.proceeds_escrow.is_none(), RoyaltiesError::InvalidProceedsRelease);
+    } else {
+        let escrow = ctx
+            .accounts
+            .proceeds_escrow
+            .as_mut()
+            .ok_or(RoyaltiesError::InvalidProceedsRelease)?;
+        escrow.royalty_listing = listing.key();
+        escrow.total_escrowed = 0;
+        escrow.total_released = 0;
+        escrow.vesting_start = 0;
+        escrow.tranches_unlocked = 0;
+        escrow.next_tranche_due = 0;
+        escrow.forfeited = 0;
+        escrow.bump = ctx
+            .bumps
+            .proceeds_escrow
+            .ok_or(RoyaltiesError::InvalidProceedsRelease)?;
+    }
+
+    let pool = &mut ctx.accounts.payout_pool;
+    pool.royalty_listing = listing.key();
+    pool.creator = listing.creator;
+    pool.total_deposited = 0;
+    pool.total_claimed = 0;
+    pool.deposited_at = 0;
+    pool.reward_per_share = 0;
+    pool.payout_interval_seconds = args.payout_interval_seconds;
+    pool.min_payout_per_period = args.min_payout_per_period;
+    pool.next_payout_due = 0;
+    pool.released_shares = 0;
+    pool.released_reward_per_share_paid = 0;
+    pool.released_pending = 0;
+    pool.bump = ctx.bumps.payout_pool;
+
+    // Describe the NFT to wallets and marketplaces through Metaplex metadata
+    let listing = &ctx.accounts.royalty_listing;
+    let collection_mint = ctx.accounts.platform_config.collection_mint;
+    let creator_key = ctx.accounts.creator.key();
+    let nft_mint_key = ctx.accounts.nft_mint.key();
+    let seeds = &[
+        ROYALTY_LISTING_SEED,
+        creator_key.as_ref(),
+        nft_mint_key.as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    metadata::create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                mint_authority: listing.to_account_info(),
+                payer: ctx.accounts.creator.to_account_info(),
+                update_authority: listing.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        DataV2 {
+            name: listing.nft_name(),
+            symbol: ROYALTY_NFT_SYMBOL.to_string(),
+            uri: listing.metadata_uri.clone(),
+            seller_fee_basis_points: listing.creator_royalty_bps,
+            // The verified listing PDA marks the NFT as issued by this program;
+            // off-platform resale royalties go to the creator
+            creators: Some(vec![
+                Creator {
+                    address: listing.key(),
+                    verified: true,
+                    share: 0,
+                },
+                Creator {
+                    address: listing.creator,
+                    verified: false,
+                    share: 100,
+                },
+            ]),
+            collection: collection_mint.map(|key| Collection {
+                verified: false,
+                key,
+            }),
+            uses: None,
+        },
+        true, // is_mutable
+        true, // update_authority_is_signer
+        None,
+    )?;
+
+    // Verify the NFT into the platform collection, signed by the config PDA
+    // as collection authority, so only program-issued NFTs carry it
+    if collection_mint.is_some() {
+        let (Some(collection_mint), Some(collection_metadata), Some(collection_master_edition)) = (
+            ctx.accounts.collection_mint.as_ref(),
+            ctx.accounts.collection_metadata.as_ref(),
+            ctx.accounts.collection_master_edition.as_ref(),
+        ) else {
+            return err!(RoyaltiesError::InvalidCollection);
+        };
+        let config_seeds = &[PLATFORM_CONFIG_SEED, &[ctx.accounts.platform_config.bump]];
+        metadata::verify_sized_collection_item(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                VerifySizedCollectionItem {
+                    payer: ctx.accounts.creator.to_account_info(),
+                    metadata: ctx.accounts.metadata.to_account_info(),
+                    collection_authority: ctx.accounts.platform_config.to_account_info(),
+                    collection_mint: collection_mint.to_account_info(),
+                    collection_metadata: collection_metadata.to_account_info(),
+                    collection_master_edition: collection_master_edition.to_account_info(),
+                },
+                &[&config_seeds[..]],
+            ),
+            None,
+        )?;
+    }
+
+    emit!(ListingCreated {
+        royalty_listing: listing.key(),
+        creator: listing.creator,
+        nft_mint: listing.nft_mint,
+        percentage_bps: listing.percentage_bps,
+        duration_seconds: listing.duration_seconds,
+        price: listing.price,
+        total_shares: listing.total_shares,
+        resale_allowed: listing.resale_allowed,
+        creator_royalty_bps: listing.creator_royalty_bps,
+        timestamp: listing.start_timestamp,
+        payment_mint: listing.payment_mint,
+        sale_mode: listing.sale_mode,
+        presale: listing.presale,
+        proceeds_release: listing.proceeds_release,
+        payout_interval_seconds: args.payout_interval_seconds,
+        min_payout_per_period: args.min_payout_per_period,
+    });
 
     Ok(())
 }
@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::state::{RoyaltyListing, PayoutPool, ListingStatus};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{RoyaltyListing, PayoutPool, ProceedsEscrow, ProceedsRelease, ListingStatus};
+use crate::state::{ROYALTY_LISTING_SEED, PAYOUT_POOL_SEED, PROCEEDS_ESCROW_SEED};
+use crate::events::PayoutDeposited;
+use crate::payments::Payment;
 use crate::errors::RoyaltiesError;
 
 #[derive(Accounts)]
@@ -9,42 +12,58 @@ pub struct DepositPayout<'info> {
     pub creator: Signer<'info>,
 
     #[account(
-        seeds = [b"royalty_listing", creator.key().as_ref(), royalty_listing.nft_mint.as_ref()],
+        mut,
+        seeds = [ROYALTY_LISTING_SEED, creator.key().as_ref(), royalty_listing.nft_mint.as_ref()],
         bump = royalty_listing.bump,
         constraint = royalty_listing.creator == creator.key() @ RoyaltiesError::Unauthorized,
-        constraint = royalty_listing.status == ListingStatus::Sold @ RoyaltiesError::ListingNotActive
+        constraint = matches!(
+            royalty_listing.status,
+            ListingStatus::Active | ListingStatus::Sold
+        ) @ RoyaltiesError::ListingNotActive,
+        constraint = royalty_listing.shares_sold > 0 @ RoyaltiesError::NoSharesSold
     )]
     pub royalty_listing: Account<'info, RoyaltyListing>,
 
     #[account(
-        init_if_needed,
-        payer = creator,
-        space = PayoutPool::LEN,
-        seeds = [b"payout_pool", royalty_listing.key().as_ref()],
-        bump
+        mut,
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump
     )]
     pub payout_pool: Account<'info, PayoutPool>,
 
-    /// Creator's USDC token account
+    /// Escrowed creator proceeds, whose tranches on-time deposits unlock
+    /// (omitted when proceeds were paid immediately)
+    #[account(
+        mut,
+        seeds = [PROCEEDS_ESCROW_SEED, royalty_listing.key().as_ref()],
+        bump = proceeds_escrow.bump
+    )]
+    pub proceeds_escrow: Option<Account<'info, ProceedsEscrow>>,
+
+    /// Creator's USDC token account (omitted for SOL listings)
     #[account(
         mut,
-        constraint = creator_usdc.owner == creator.key()
+        constraint = creator_usdc.owner == creator.key(),
+        constraint = creator_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
     )]
-    pub creator_usdc: Account<'info, TokenAccount>,
+    pub creator_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Payout pool's USDC vault
+    /// Payout pool's USDC vault (omitted for SOL listings, whose pool holds the lamports)
     #[account(
         init_if_needed,
         payer = creator,
         token::mint = usdc_mint,
         token::authority = payout_pool,
+        token::token_program = token_program,
     )]
-    pub pool_vault: Account<'info, TokenAccount>,
+    pub pool_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// USDC mint
-    pub usdc_mint: Account<'info, anchor_spl::token::Mint>,
+    /// Listing's payment mint (USDC or another allowlisted mint; omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<InterfaceAccount<'info, Mint>>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program of the payment mint (Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -53,44 +72,71 @@ pub fn handler(ctx: Context<DepositPayout>, amount: u64) -> Result<()> {
     require!(amount > 0, RoyaltiesError::InvalidPrice);
 
     let clock = Clock::get()?;
+    require!(
+        !ctx.accounts.royalty_listing.is_expired(clock.unix_timestamp),
+        RoyaltiesError::ListingExpired
+    );
 
-    // Transfer USDC to pool vault
-    token::transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.creator_usdc.to_account_info(),
-                to: ctx.accounts.pool_vault.to_account_info(),
-                authority: ctx.accounts.creator.to_account_info(),
-            },
-        ),
+    // Transfer to the pool vault, or straight into the pool for SOL listings.
+    // Only what the pool actually received (net of any transfer fee) is distributable.
+    let received = Payment {
+        sol: ctx.accounts.royalty_listing.pays_in_sol(),
+        payer: &ctx.accounts.creator,
+        from: ctx.accounts.creator_usdc.as_ref(),
+        mint: ctx.accounts.usdc_mint.as_ref(),
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+    }
+    .pay(
+        ctx.accounts.payout_pool.to_account_info(),
+        ctx.accounts.pool_vault.as_ref(),
         amount,
     )?;
 
-    // Update payout pool
+    // Update payout pool; while the primary sale runs, only the shares sold share in it
+    let shares_sold = ctx.accounts.royalty_listing.shares_sold;
     let pool = &mut ctx.accounts.payout_pool;
-    
-    // If this is a new period, reset tracking
-    if pool.total_deposited == pool.total_claimed || pool.total_deposited == 0 {
-        pool.period = pool.period.checked_add(1).ok_or(RoyaltiesError::Overflow)?;
-        pool.total_deposited = 0;
-        pool.total_claimed = 0;
+    pool.record_deposit(received, shares_sold)?;
+    pool.deposited_at = clock.unix_timestamp;
+    // Only a deposit of the committed size settles a scheduled payout
+    if received >= pool.min_payout_per_period {
+        pool.advance_schedule(clock.unix_timestamp);
     }
 
-    pool.royalty_listing = ctx.accounts.royalty_listing.key();
-    pool.creator = ctx.accounts.creator.key();
-    pool.total_deposited = pool
-        .total_deposited
-        .checked_add(amount)
-        .ok_or(RoyaltiesError::Overflow)?;
-    pool.deposited_at = clock.unix_timestamp;
-    pool.bump = ctx.bumps.payout_pool;
+    // Catching up on every missed payout lifts the delinquency flag
+    let next_payout_due = pool.next_payout_due;
+    if !pool.is_overdue(clock.unix_timestamp) {
+        ctx.accounts.royalty_listing.delinquent = false;
+    }
 
-    msg!(
-        "Deposited {} USDC for payout period {}",
-        amount as f64 / 1_000_000.0,
-        pool.period
-    );
+    // Tranches only fall due once the listing sells out
+    let release = ctx.accounts.royalty_listing.proceeds_release;
+    let sold_out = ctx.accounts.royalty_listing.status == ListingStatus::Sold;
+    let tranche_unlocked = match ctx.accounts.proceeds_escrow.as_mut() {
+        Some(escrow) if sold_out => escrow.record_deposit(release, received, clock.unix_timestamp),
+        Some(_) => false,
+        None => {
+            require!(
+                !matches!(release, ProceedsRelease::PayoutTranches { .. }),
+                RoyaltiesError::InvalidProceedsRelease
+            );
+            false
+        }
+    };
+
+    let pool = &ctx.accounts.payout_pool;
+    emit!(PayoutDeposited {
+        payout_pool: pool.key(),
+        royalty_listing: pool.royalty_listing,
+        creator: pool.creator,
+        amount: received,
+        total_deposited: pool.total_deposited,
+        reward_per_share: pool.reward_per_share,
+        timestamp: clock.unix_timestamp,
+        tranche_unlocked,
+        next_payout_due,
+        delinquent: ctx.accounts.royalty_listing.delinquent,
+    });
 
     Ok(())
 }
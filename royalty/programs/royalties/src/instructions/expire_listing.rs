@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::{RoyaltyListing, ListingStatus};
+use crate::state::ROYALTY_LISTING_SEED;
+use crate::events::ListingExpired;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct ExpireListing<'info> {
+    #[account(
+        mut,
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = matches!(
+            royalty_listing.status,
+            ListingStatus::Active | ListingStatus::Sold
+        ) @ RoyaltiesError::ListingNotActive
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+}
+
+pub fn handler(ctx: Context<ExpireListing>) -> Result<()> {
+    let clock = Clock::get()?;
+    let listing = &mut ctx.accounts.royalty_listing;
+    require!(
+        listing.is_expired(clock.unix_timestamp),
+        RoyaltiesError::ListingNotExpired
+    );
+
+    listing.status = ListingStatus::Expired;
+
+    emit!(ListingExpired {
+        royalty_listing: listing.key(),
+        creator: listing.creator,
+        nft_mint: listing.nft_mint,
+        expired_at: listing.expires_at().unwrap_or_default(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{RoyaltyListing, PayoutPool, ProceedsEscrow};
+use crate::state::{ROYALTY_LISTING_SEED, PAYOUT_POOL_SEED, PROCEEDS_ESCROW_SEED};
+use crate::events::ProceedsForfeited;
+use crate::payments::Escrow;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct ForfeitProceeds<'info> {
+    /// Anyone may forfeit; pays for the pool vault if it doesn't exist yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = royalty_listing.shares_sold > 0 @ RoyaltiesError::NoSharesSold
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    #[account(
+        mut,
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump
+    )]
+    pub payout_pool: Account<'info, PayoutPool>,
+
+    #[account(
+        mut,
+        seeds = [PROCEEDS_ESCROW_SEED, royalty_listing.key().as_ref()],
+        bump = proceeds_escrow.bump
+    )]
+    pub proceeds_escrow: Account<'info, ProceedsEscrow>,
+
+    /// Proceeds escrow's USDC vault (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = proceeds_vault.owner == proceeds_escrow.key() @ RoyaltiesError::Unauthorized,
+        constraint = proceeds_vault.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub proceeds_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Payout pool's USDC vault (omitted for SOL listings, whose pool holds the lamports)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        token::mint = usdc_mint,
+        token::authority = payout_pool,
+        token::token_program = token_program,
+    )]
+    pub pool_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Listing's payment mint (omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program of the payment mint (Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ForfeitProceeds>) -> Result<()> {
+    let clock = Clock::get()?;
+    let listing = &ctx.accounts.royalty_listing;
+    let amount = ctx.accounts.proceeds_escrow.forfeitable(
+        listing.proceeds_release,
+        listing.is_expired(clock.unix_timestamp),
+        clock.unix_timestamp,
+    );
+    require!(amount > 0, RoyaltiesError::ProceedsNotForfeitable);
+
+    // Locked proceeds go to holders as a payout; any transfer fee is
+    // withheld from the pool's side
+    let royalty_listing_key = listing.key();
+    let escrow = &ctx.accounts.proceeds_escrow;
+    let seeds = &[PROCEEDS_ESCROW_SEED, royalty_listing_key.as_ref(), &[escrow.bump]];
+    let received = Escrow {
+        sol: listing.pays_in_sol(),
+        authority: escrow.to_account_info(),
+        signer_seeds: &[&seeds[..]],
+        vault: ctx.accounts.proceeds_vault.as_ref(),
+        mint: ctx.accounts.usdc_mint.as_ref(),
+        token_program: &ctx.accounts.token_program,
+    }
+    .pay(
+        ctx.accounts.payout_pool.to_account_info(),
+        ctx.accounts.pool_vault.as_ref(),
+        amount,
+    )?;
+
+    // Only sold shares hold payout positions
+    let shares_sold = listing.shares_sold;
+    let pool = &mut ctx.accounts.payout_pool;
+    pool.record_deposit(received, shares_sold)?;
+    pool.deposited_at = clock.unix_timestamp;
+
+    let escrow = &mut ctx.accounts.proceeds_escrow;
+    escrow.forfeited = amount;
+
+    emit!(ProceedsForfeited {
+        proceeds_escrow: escrow.key(),
+        royalty_listing: royalty_listing_key,
+        payout_pool: pool.key(),
+        amount: received,
+        total_escrowed: escrow.total_escrowed,
+        reward_per_share: pool.reward_per_share,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::state::PlatformConfig;
+use crate::state::PLATFORM_CONFIG_SEED;
+use crate::events::PlatformInitialized;
 use crate::errors::RoyaltiesError;
 
 #[derive(Accounts)]
@@ -11,7 +13,7 @@ pub struct Initialize<'info> {
         init,
         payer = authority,
         space = PlatformConfig::LEN,
-        seeds = [b"platform_config"],
+        seeds = [PLATFORM_CONFIG_SEED],
         bump
     )]
     pub platform_config: Account<'info, PlatformConfig>,
@@ -28,13 +30,24 @@ pub fn handler(ctx: Context<Initialize>, platform_fee_bps: u16) -> Result<()> {
 
     let config = &mut ctx.accounts.platform_config;
     config.authority = ctx.accounts.authority.key();
+    config.pending_authority = None;
     config.treasury = ctx.accounts.treasury.key();
     config.platform_fee_bps = platform_fee_bps;
     config.secondary_fee_bps = 250; // 2.5% default for secondary
     config.total_fees_collected = 0;
+    config.payment_mints = Vec::new();
+    config.collection_mint = None;
     config.bump = ctx.bumps.platform_config;
 
-    msg!("Platform initialized with {}bps fee", platform_fee_bps);
+    emit!(PlatformInitialized {
+        platform_config: config.key(),
+        authority: config.authority,
+        treasury: config.treasury,
+        platform_fee_bps: config.platform_fee_bps,
+        secondary_fee_bps: config.secondary_fee_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
 
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::state::{RoyaltyListing, ResaleListing, ListingStatus};
+use anchor_spl::token_interface::{self, Mint, Token2022, TokenAccount, TransferChecked};
+use crate::state::{RoyaltyListing, ResaleListing, ListingStatus, PayoutPool, PayoutClaim};
+use crate::state::{ROYALTY_LISTING_SEED, RESALE_LISTING_SEED, PAYOUT_POOL_SEED, PAYOUT_CLAIM_SEED};
+use crate::events::ResaleListed;
 use crate::errors::RoyaltiesError;
 
 #[derive(Accounts)]
@@ -9,7 +11,7 @@ pub struct ListForResale<'info> {
     pub seller: Signer<'info>,
 
     #[account(
-        seeds = [b"royalty_listing", royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
         bump = royalty_listing.bump,
         constraint = royalty_listing.status == ListingStatus::Sold @ RoyaltiesError::ListingNotActive,
         constraint = royalty_listing.resale_allowed @ RoyaltiesError::ResaleNotAllowed
@@ -20,53 +22,91 @@ pub struct ListForResale<'info> {
         init,
         payer = seller,
         space = ResaleListing::LEN,
-        seeds = [b"resale_listing", royalty_listing.key().as_ref(), seller.key().as_ref()],
+        seeds = [RESALE_LISTING_SEED, royalty_listing.key().as_ref(), seller.key().as_ref()],
         bump
     )]
     pub resale_listing: Account<'info, ResaleListing>,
 
+    #[account(
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump
+    )]
+    pub payout_pool: Account<'info, PayoutPool>,
+
+    /// Seller's position in the payout pool
+    #[account(
+        seeds = [PAYOUT_CLAIM_SEED, payout_pool.key().as_ref(), seller.key().as_ref()],
+        bump = seller_payout_claim.bump
+    )]
+    pub seller_payout_claim: Account<'info, PayoutClaim>,
+
     /// Seller's NFT token account
     #[account(
         mut,
         constraint = seller_nft.owner == seller.key() @ RoyaltiesError::NotOwner,
-        constraint = seller_nft.mint == royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
-        constraint = seller_nft.amount == 1 @ RoyaltiesError::NotOwner
+        constraint = seller_nft.mint == royalty_listing.nft_mint @ RoyaltiesError::Unauthorized
     )]
-    pub seller_nft: Account<'info, TokenAccount>,
+    pub seller_nft: InterfaceAccount<'info, TokenAccount>,
 
-    /// Escrow account to hold NFT during listing
+    /// Escrow account to hold shares during listing
     #[account(
         init_if_needed,
         payer = seller,
         token::mint = nft_mint,
         token::authority = resale_listing,
+        token::token_program = token_program,
     )]
-    pub escrow_nft: Account<'info, TokenAccount>,
+    pub escrow_nft: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: NFT mint
-    pub nft_mint: UncheckedAccount<'info>,
+    #[account(
+        address = royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
+        mint::token_program = token_program
+    )]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
 
-    pub token_program: Program<'info, Token>,
+    /// Royalty NFTs are always Token-2022 mints
+    pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handler(ctx: Context<ListForResale>, price: u64) -> Result<()> {
+pub fn handler(
+    ctx: Context<ListForResale>,
+    price: u64,
+    amount: u64,
+    expires_at: Option<i64>,
+) -> Result<()> {
     require!(price > 0, RoyaltiesError::InvalidPrice);
+    require!(amount > 0, RoyaltiesError::InvalidShareAmount);
+    // Shares must be held and credited to the seller's payout position
+    require!(
+        ctx.accounts.seller_nft.amount >= amount
+            && ctx.accounts.seller_payout_claim.shares >= amount,
+        RoyaltiesError::NotOwner
+    );
 
     let clock = Clock::get()?;
+    require!(
+        !ctx.accounts.royalty_listing.is_expired(clock.unix_timestamp),
+        RoyaltiesError::ListingExpired
+    );
+    if let Some(expires_at) = expires_at {
+        require!(expires_at > clock.unix_timestamp, RoyaltiesError::InvalidResaleExpiry);
+    }
 
-    // Transfer NFT to escrow
-    token::transfer(
+    // Transfer shares to escrow
+    token_interface::transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.seller_nft.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
                 to: ctx.accounts.escrow_nft.to_account_info(),
                 authority: ctx.accounts.seller.to_account_info(),
             },
         ),
-        1,
+        amount,
+        ctx.accounts.nft_mint.decimals,
     )?;
 
     // Create resale listing
@@ -74,11 +114,23 @@ pub fn handler(ctx: Context<ListForResale>, price: u64) -> Result<()> {
     resale.seller = ctx.accounts.seller.key();
     resale.royalty_listing = ctx.accounts.royalty_listing.key();
     resale.nft_mint = ctx.accounts.royalty_listing.nft_mint;
+    resale.amount = amount;
     resale.price = price;
     resale.listed_at = clock.unix_timestamp;
+    resale.updated_at = clock.unix_timestamp;
+    resale.expires_at = expires_at;
     resale.bump = ctx.bumps.resale_listing;
 
-    msg!("Listed for resale at {} USDC", price as f64 / 1_000_000.0);
+    emit!(ResaleListed {
+        resale_listing: resale.key(),
+        royalty_listing: resale.royalty_listing,
+        seller: resale.seller,
+        nft_mint: resale.nft_mint,
+        amount,
+        price,
+        timestamp: resale.listed_at,
+        expires_at,
+    });
 
     Ok(())
 }
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{RoyaltyListing, Offer, ListingStatus};
+use crate::state::{ROYALTY_LISTING_SEED, OFFER_SEED};
+use crate::events::OfferMade;
+use crate::payments::Payment;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct MakeOffer<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = royalty_listing.status == ListingStatus::Sold @ RoyaltiesError::ListingNotActive,
+        constraint = royalty_listing.resale_allowed @ RoyaltiesError::ResaleNotAllowed
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = Offer::LEN,
+        seeds = [OFFER_SEED, royalty_listing.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    /// Bidder's USDC (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = bidder_usdc.owner == bidder.key(),
+        constraint = bidder_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub bidder_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow holding the bid (omitted for SOL listings, whose offer holds the lamports)
+    #[account(
+        init,
+        payer = bidder,
+        token::mint = usdc_mint,
+        token::authority = offer,
+        token::token_program = token_program,
+    )]
+    pub offer_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Listing's payment mint (omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program of the payment mint (Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// `price` is the total bid for `amount` shares.
+pub fn handler(ctx: Context<MakeOffer>, amount: u64, price: u64) -> Result<()> {
+    require!(price > 0, RoyaltiesError::InvalidPrice);
+    require!(
+        amount > 0 && amount <= ctx.accounts.royalty_listing.total_shares,
+        RoyaltiesError::InvalidShareAmount
+    );
+
+    let clock = Clock::get()?;
+    require!(
+        !ctx.accounts.royalty_listing.is_expired(clock.unix_timestamp),
+        RoyaltiesError::ListingExpired
+    );
+
+    // Escrow the bid in the vault, or in the offer itself for SOL listings
+    let escrowed = Payment {
+        sol: ctx.accounts.royalty_listing.pays_in_sol(),
+        payer: &ctx.accounts.bidder,
+        from: ctx.accounts.bidder_usdc.as_ref(),
+        mint: ctx.accounts.usdc_mint.as_ref(),
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+    }
+    .pay(
+        ctx.accounts.offer.to_account_info(),
+        ctx.accounts.offer_vault.as_ref(),
+        price,
+    )?;
+
+    let offer = &mut ctx.accounts.offer;
+    offer.bidder = ctx.accounts.bidder.key();
+    offer.royalty_listing = ctx.accounts.royalty_listing.key();
+    offer.nft_mint = ctx.accounts.royalty_listing.nft_mint;
+    offer.amount = amount;
+    offer.price = escrowed;
+    offer.created_at = clock.unix_timestamp;
+    offer.bump = ctx.bumps.offer;
+
+    emit!(OfferMade {
+        offer: offer.key(),
+        royalty_listing: offer.royalty_listing,
+        bidder: offer.bidder,
+        nft_mint: offer.nft_mint,
+        amount,
+        price: escrowed,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
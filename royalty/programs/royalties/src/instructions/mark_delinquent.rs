@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::{RoyaltyListing, PayoutPool, ListingStatus};
+use crate::state::{ROYALTY_LISTING_SEED, PAYOUT_POOL_SEED};
+use crate::events::ListingDelinquent;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct MarkDelinquent<'info> {
+    #[account(
+        mut,
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = matches!(
+            royalty_listing.status,
+            ListingStatus::Active | ListingStatus::Sold
+        ) @ RoyaltiesError::ListingNotActive,
+        constraint = !royalty_listing.delinquent @ RoyaltiesError::AlreadyDelinquent
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    #[account(
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump
+    )]
+    pub payout_pool: Account<'info, PayoutPool>,
+}
+
+pub fn handler(ctx: Context<MarkDelinquent>) -> Result<()> {
+    let clock = Clock::get()?;
+    let listing = &mut ctx.accounts.royalty_listing;
+    require!(
+        !listing.is_expired(clock.unix_timestamp),
+        RoyaltiesError::ListingExpired
+    );
+    let pool = &ctx.accounts.payout_pool;
+    require!(
+        pool.is_overdue(clock.unix_timestamp),
+        RoyaltiesError::PayoutNotOverdue
+    );
+
+    listing.delinquent = true;
+
+    emit!(ListingDelinquent {
+        royalty_listing: listing.key(),
+        payout_pool: pool.key(),
+        creator: listing.creator,
+        due_at: pool.next_payout_due,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,18 +1,62 @@
+#![allow(ambiguous_glob_reexports)]
+
 pub mod initialize;
+pub mod update_config;
+pub mod propose_authority;
+pub mod accept_authority;
+pub mod add_payment_mint;
+pub mod remove_payment_mint;
+pub mod create_collection;
 pub mod create_listing;
+pub mod update_listing;
+pub mod cancel_listing;
+pub mod close_primary_sale;
+pub mod expire_listing;
 pub mod buy_listing;
+pub mod place_bid;
+pub mod settle_auction;
 pub mod list_for_resale;
+pub mod update_resale_price;
 pub mod buy_resale;
 pub mod cancel_resale;
+pub mod close_expired_resale;
+pub mod make_offer;
+pub mod cancel_offer;
+pub mod accept_offer;
 pub mod deposit_payout;
 pub mod claim_payout;
+pub mod sync_payout_claim;
+pub mod release_proceeds;
+pub mod forfeit_proceeds;
+pub mod mark_delinquent;
 
 pub use initialize::*;
+pub use update_config::*;
+pub use propose_authority::*;
+pub use accept_authority::*;
+pub use add_payment_mint::*;
+pub use remove_payment_mint::*;
+pub use create_collection::*;
 pub use create_listing::*;
+pub use update_listing::*;
+pub use cancel_listing::*;
+pub use close_primary_sale::*;
+pub use expire_listing::*;
 pub use buy_listing::*;
+pub use place_bid::*;
+pub use settle_auction::*;
 pub use list_for_resale::*;
+pub use update_resale_price::*;
 pub use buy_resale::*;
 pub use cancel_resale::*;
+pub use close_expired_resale::*;
+pub use make_offer::*;
+pub use cancel_offer::*;
+pub use accept_offer::*;
 pub use deposit_payout::*;
 pub use claim_payout::*;
+pub use sync_payout_claim::*;
+pub use release_proceeds::*;
+pub use forfeit_proceeds::*;
+pub use mark_delinquent::*;
 
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{RoyaltyListing, ListingStatus, SaleMode, Auction};
+use crate::state::{ROYALTY_LISTING_SEED, AUCTION_SEED};
+use crate::events::BidPlaced;
+use crate::payments::{Escrow, Payment};
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = royalty_listing.status == ListingStatus::Active @ RoyaltiesError::ListingNotActive
+    )]
+    pub royalty_listing: Box<Account<'info, RoyaltyListing>>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, royalty_listing.key().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    /// Auction's USDC vault, created with the first bid (omitted for SOL listings,
+    /// whose auction holds the lamports)
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub auction_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Bidder's USDC (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = bidder_usdc.owner == bidder.key(),
+        constraint = bidder_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub bidder_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Bidder being outbid and refunded; any account (e.g. the bidder) for the first bid
+    #[account(
+        mut,
+        constraint = auction.highest_bidder.is_none_or(|highest| highest == previous_bidder.key()) @ RoyaltiesError::Unauthorized
+    )]
+    pub previous_bidder: UncheckedAccount<'info>,
+
+    /// Previous bidder's USDC account, recreated if it was closed so a refund
+    /// can't be blocked (omitted for the first bid and SOL listings)
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = previous_bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub previous_bidder_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Listing's payment mint (omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Token program of the payment mint (Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// `amount` is the bid for the whole share supply.
+pub fn handler(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+    let SaleMode::EnglishAuction { reserve_price, end_time } = ctx.accounts.royalty_listing.sale_mode
+    else {
+        return err!(RoyaltiesError::NotAnAuction);
+    };
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp < end_time, RoyaltiesError::AuctionEnded);
+
+    // Escrow the new bid first; only what the auction received counts
+    let sol = ctx.accounts.royalty_listing.pays_in_sol();
+    let received = Payment {
+        sol,
+        payer: &ctx.accounts.bidder,
+        from: ctx.accounts.bidder_usdc.as_deref(),
+        mint: ctx.accounts.usdc_mint.as_deref(),
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+    }
+    .pay(
+        ctx.accounts.auction.to_account_info(),
+        ctx.accounts.auction_vault.as_deref(),
+        amount,
+    )?;
+    let auction = &ctx.accounts.auction;
+    require!(
+        received >= reserve_price && received > auction.highest_bid,
+        RoyaltiesError::BidTooLow
+    );
+
+    // Refund the bid this one beats
+    let previous_bidder = auction.highest_bidder;
+    let refunded = auction.highest_bid;
+    if previous_bidder.is_some() {
+        let royalty_listing_key = ctx.accounts.royalty_listing.key();
+        let seeds = &[AUCTION_SEED, royalty_listing_key.as_ref(), &[auction.bump]];
+        Escrow {
+            sol,
+            authority: auction.to_account_info(),
+            signer_seeds: &[&seeds[..]],
+            vault: ctx.accounts.auction_vault.as_deref(),
+            mint: ctx.accounts.usdc_mint.as_deref(),
+            token_program: &ctx.accounts.token_program,
+        }
+        .pay(
+            ctx.accounts.previous_bidder.to_account_info(),
+            ctx.accounts.previous_bidder_usdc.as_deref(),
+            refunded,
+        )?;
+    }
+
+    let auction = &mut ctx.accounts.auction;
+    auction.highest_bidder = Some(ctx.accounts.bidder.key());
+    auction.highest_bid = received;
+    auction.bid_count = auction
+        .bid_count
+        .checked_add(1)
+        .ok_or(RoyaltiesError::Overflow)?;
+
+    emit!(BidPlaced {
+        auction: auction.key(),
+        royalty_listing: auction.royalty_listing,
+        bidder: ctx.accounts.bidder.key(),
+        amount: received,
+        previous_bidder,
+        refunded,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::PlatformConfig;
+use crate::state::PLATFORM_CONFIG_SEED;
+use crate::events::AuthorityProposed;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED],
+        bump = platform_config.bump,
+        has_one = authority @ RoyaltiesError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.platform_config;
+
+    // Proposing the current authority clears any pending transfer
+    config.pending_authority = if new_authority == config.authority {
+        None
+    } else {
+        Some(new_authority)
+    };
+
+    emit!(AuthorityProposed {
+        platform_config: config.key(),
+        authority: config.authority,
+        pending_authority: config.pending_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
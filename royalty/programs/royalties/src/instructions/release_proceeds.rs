@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{RoyaltyListing, ProceedsEscrow};
+use crate::state::{ROYALTY_LISTING_SEED, PROCEEDS_ESCROW_SEED};
+use crate::events::ProceedsReleased;
+use crate::payments::Escrow;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct ReleaseProceeds<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [ROYALTY_LISTING_SEED, creator.key().as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = royalty_listing.creator == creator.key() @ RoyaltiesError::Unauthorized
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    #[account(
+        mut,
+        seeds = [PROCEEDS_ESCROW_SEED, royalty_listing.key().as_ref()],
+        bump = proceeds_escrow.bump
+    )]
+    pub proceeds_escrow: Account<'info, ProceedsEscrow>,
+
+    /// Proceeds escrow's USDC vault (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = proceeds_vault.owner == proceeds_escrow.key() @ RoyaltiesError::Unauthorized,
+        constraint = proceeds_vault.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub proceeds_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator's USDC token account (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = creator_usdc.owner == creator.key(),
+        constraint = creator_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub creator_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Listing's payment mint (omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token program of the payment mint (Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<ReleaseProceeds>) -> Result<()> {
+    let clock = Clock::get()?;
+    let release = ctx.accounts.royalty_listing.proceeds_release;
+    let escrow = &ctx.accounts.proceeds_escrow;
+    let amount = escrow.releasable(release, clock.unix_timestamp);
+    require!(amount > 0, RoyaltiesError::NoProceedsToRelease);
+
+    // Any transfer fee is withheld from the creator's side
+    let royalty_listing_key = ctx.accounts.royalty_listing.key();
+    let seeds = &[PROCEEDS_ESCROW_SEED, royalty_listing_key.as_ref(), &[escrow.bump]];
+    Escrow {
+        sol: ctx.accounts.royalty_listing.pays_in_sol(),
+        authority: escrow.to_account_info(),
+        signer_seeds: &[&seeds[..]],
+        vault: ctx.accounts.proceeds_vault.as_ref(),
+        mint: ctx.accounts.usdc_mint.as_ref(),
+        token_program: &ctx.accounts.token_program,
+    }
+    .pay(
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.creator_usdc.as_ref(),
+        amount,
+    )?;
+
+    let escrow = &mut ctx.accounts.proceeds_escrow;
+    escrow.total_released = escrow
+        .total_released
+        .checked_add(amount)
+        .ok_or(RoyaltiesError::Overflow)?;
+
+    emit!(ProceedsReleased {
+        proceeds_escrow: escrow.key(),
+        royalty_listing: royalty_listing_key,
+        creator: ctx.accounts.creator.key(),
+        amount,
+        total_released: escrow.total_released,
+        total_escrowed: escrow.total_escrowed,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
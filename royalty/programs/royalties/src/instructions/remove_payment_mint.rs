@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::PlatformConfig;
+use crate::state::PLATFORM_CONFIG_SEED;
+use crate::events::PaymentMintRemoved;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct RemovePaymentMint<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED],
+        bump = platform_config.bump,
+        has_one = authority @ RoyaltiesError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<RemovePaymentMint>, payment_mint: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.platform_config;
+
+    // Existing listings keep settling in the mint they recorded
+    let index = config
+        .payment_mints
+        .iter()
+        .position(|mint| *mint == payment_mint)
+        .ok_or(RoyaltiesError::PaymentMintNotAllowed)?;
+    config.payment_mints.remove(index);
+
+    emit!(PaymentMintRemoved {
+        platform_config: config.key(),
+        payment_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,336 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata::instructions::{
+    CreateMasterEditionV3Cpi, CreateMasterEditionV3CpiAccounts, CreateMasterEditionV3InstructionArgs,
+};
+use anchor_spl::metadata::Metadata;
+use anchor_spl::token_interface::{self, Mint, MintTo, Token2022, TokenAccount, TokenInterface};
+use crate::state::{RoyaltyListing, ListingStatus, SaleMode, ProceedsRelease, ProceedsEscrow, Auction, PlatformConfig, PayoutPool, PayoutClaim};
+use crate::state::{PLATFORM_CONFIG_SEED, ROYALTY_LISTING_SEED, AUCTION_SEED, PAYOUT_POOL_SEED, PAYOUT_CLAIM_SEED, PROCEEDS_ESCROW_SEED};
+use crate::events::AuctionSettled;
+use crate::payments::{Escrow, PrimarySplit};
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    /// Anyone may settle an ended auction; pays for the winner's accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Creator receiving proceeds and the auction rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    /// CHECK: Highest bidder receiving the shares
+    #[account(mut)]
+    pub winner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// CHECK: Platform treasury receiving fees
+    #[account(mut, address = platform_config.treasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ROYALTY_LISTING_SEED, creator.key().as_ref(), nft_mint.key().as_ref()],
+        bump = royalty_listing.bump,
+        constraint = matches!(
+            royalty_listing.status,
+            ListingStatus::Active | ListingStatus::Expired
+        ) @ RoyaltiesError::ListingNotActive
+    )]
+    pub royalty_listing: Box<Account<'info, RoyaltyListing>>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, royalty_listing.key().as_ref()],
+        bump = auction.bump,
+        constraint = auction.highest_bidder == Some(winner.key()) @ RoyaltiesError::Unauthorized,
+        close = creator
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    /// Auction's USDC vault (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = auction_vault.owner == auction.key() @ RoyaltiesError::Unauthorized,
+        constraint = auction_vault.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub auction_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        constraint = nft_mint.key() == royalty_listing.nft_mint @ RoyaltiesError::Unauthorized,
+        mint::token_program = token_program
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump
+    )]
+    pub payout_pool: Box<Account<'info, PayoutPool>>,
+
+    /// Winner's position in the payout pool
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PayoutClaim::LEN,
+        seeds = [PAYOUT_CLAIM_SEED, payout_pool.key().as_ref(), winner.key().as_ref()],
+        bump
+    )]
+    pub winner_payout_claim: Box<Account<'info, PayoutClaim>>,
+
+    /// Winner's NFT token account
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = winner,
+        associated_token::token_program = token_program,
+    )]
+    pub winner_nft: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Winner's USDC account, refunded if the listing term ended first (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = winner_usdc.owner == winner.key(),
+        constraint = winner_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub winner_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Creator's USDC token account (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = creator_usdc.owner == creator.key(),
+        constraint = creator_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub creator_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Treasury's USDC token account (omitted for SOL listings)
+    #[account(
+        mut,
+        constraint = treasury_usdc.owner == treasury.key(),
+        constraint = treasury_usdc.mint == royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint
+    )]
+    pub treasury_usdc: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Escrow receiving the creator's proceeds (omitted when they are paid immediately)
+    #[account(
+        mut,
+        seeds = [PROCEEDS_ESCROW_SEED, royalty_listing.key().as_ref()],
+        bump = proceeds_escrow.bump
+    )]
+    pub proceeds_escrow: Option<Box<Account<'info, ProceedsEscrow>>>,
+
+    /// Proceeds escrow's USDC vault (omitted for SOL listings and immediate proceeds)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = proceeds_escrow,
+        associated_token::token_program = payment_token_program,
+    )]
+    pub proceeds_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Listing's payment mint (omitted for SOL listings)
+    #[account(address = royalty_listing.payment_mint @ RoyaltiesError::InvalidPaymentMint)]
+    pub usdc_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: Metaplex metadata account of the NFT mint
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition, created when a single-share listing sells
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    /// Royalty NFTs are always Token-2022 mints
+    pub token_program: Program<'info, Token2022>,
+    /// Token program of the payment mint (Token or Token-2022)
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SettleAuction>) -> Result<()> {
+    let SaleMode::EnglishAuction { end_time, .. } = ctx.accounts.royalty_listing.sale_mode else {
+        return err!(RoyaltiesError::NotAnAuction);
+    };
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp >= end_time, RoyaltiesError::AuctionNotEnded);
+
+    let listing = &ctx.accounts.royalty_listing;
+    let price = ctx.accounts.auction.highest_bid;
+    let amount = listing.total_shares;
+    // Shares of a lapsed royalty term are worthless, so the winner gets their bid back
+    let refunded = listing.status == ListingStatus::Expired || listing.is_expired(clock.unix_timestamp);
+
+    let creator_key = ctx.accounts.creator.key();
+    let nft_mint_key = ctx.accounts.nft_mint.key();
+    let listing_seeds = &[
+        ROYALTY_LISTING_SEED,
+        creator_key.as_ref(),
+        nft_mint_key.as_ref(),
+        &[listing.bump],
+    ];
+    let listing_signer = &[&listing_seeds[..]];
+
+    let mut split = PrimarySplit {
+        platform_fee: 0,
+        creator_amount: 0,
+    };
+    if !refunded {
+        // Mint the whole supply to the winner
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.nft_mint.to_account_info(),
+                    to: ctx.accounts.winner_nft.to_account_info(),
+                    authority: ctx.accounts.royalty_listing.to_account_info(),
+                },
+                listing_signer,
+            ),
+            amount,
+        )?;
+
+        // A single-share listing is a 1-of-1 NFT: lock its supply with a master edition
+        if amount == 1 {
+            CreateMasterEditionV3Cpi::new(
+                &ctx.accounts.token_metadata_program.to_account_info(),
+                CreateMasterEditionV3CpiAccounts {
+                    edition: &ctx.accounts.master_edition.to_account_info(),
+                    mint: &ctx.accounts.nft_mint.to_account_info(),
+                    update_authority: &ctx.accounts.royalty_listing.to_account_info(),
+                    mint_authority: &ctx.accounts.royalty_listing.to_account_info(),
+                    payer: &ctx.accounts.payer.to_account_info(),
+                    metadata: &ctx.accounts.metadata.to_account_info(),
+                    token_program: &ctx.accounts.token_program.to_account_info(),
+                    system_program: &ctx.accounts.system_program.to_account_info(),
+                    rent: None,
+                },
+                CreateMasterEditionV3InstructionArgs {
+                    max_supply: Some(0),
+                },
+            )
+            .invoke_signed(listing_signer)?;
+        }
+
+        // Credit the shares to the winner's payout position
+        let reward_per_share = ctx.accounts.payout_pool.reward_per_share;
+        let winner_claim = &mut ctx.accounts.winner_payout_claim;
+        winner_claim.payout_pool = ctx.accounts.payout_pool.key();
+        winner_claim.holder = ctx.accounts.winner.key();
+        winner_claim.add_shares(amount, reward_per_share)?;
+        winner_claim.bump = ctx.bumps.winner_payout_claim;
+
+        split = PrimarySplit::new(price, ctx.accounts.platform_config.platform_fee_bps)?;
+    }
+
+    // Pay out of the auction escrow: proceeds to creator and platform, or the refund
+    let royalty_listing_key = ctx.accounts.royalty_listing.key();
+    let auction_seeds = &[
+        AUCTION_SEED,
+        royalty_listing_key.as_ref(),
+        &[ctx.accounts.auction.bump],
+    ];
+    let escrow = Escrow {
+        sol: ctx.accounts.royalty_listing.pays_in_sol(),
+        authority: ctx.accounts.auction.to_account_info(),
+        signer_seeds: &[&auction_seeds[..]],
+        vault: ctx.accounts.auction_vault.as_deref(),
+        mint: ctx.accounts.usdc_mint.as_deref(),
+        token_program: &ctx.accounts.payment_token_program,
+    };
+    let mut fee_received = 0;
+    let mut creator_received = 0;
+    if refunded {
+        escrow.pay(
+            ctx.accounts.winner.to_account_info(),
+            ctx.accounts.winner_usdc.as_deref(),
+            price,
+        )?;
+    } else {
+        // Escrowed proceeds go to the proceeds escrow instead of the creator
+        creator_received = match ctx.accounts.royalty_listing.proceeds_release {
+            ProceedsRelease::Immediate => escrow.pay(
+                ctx.accounts.creator.to_account_info(),
+                ctx.accounts.creator_usdc.as_deref(),
+                split.creator_amount,
+            )?,
+            _ => escrow.pay(
+                ctx.accounts
+                    .proceeds_escrow
+                    .as_ref()
+                    .ok_or(RoyaltiesError::InvalidProceedsRelease)?
+                    .to_account_info(),
+                ctx.accounts.proceeds_vault.as_deref(),
+                split.creator_amount,
+            )?,
+        };
+        // Transfer fees on the payment mint are borne by the recipients
+        fee_received = escrow.pay(
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.treasury_usdc.as_deref(),
+            split.platform_fee,
+        )?;
+    }
+    // Anything sent to the vault beyond the bid goes to the creator
+    escrow.close_vault(
+        ctx.accounts.creator_usdc.as_deref(),
+        ctx.accounts.creator.to_account_info(),
+    )?;
+
+    let listing = &mut ctx.accounts.royalty_listing;
+    if refunded {
+        listing.status = ListingStatus::Expired;
+    } else {
+        listing.shares_sold = amount;
+        listing.status = ListingStatus::Sold;
+        ctx.accounts.payout_pool.start_schedule(clock.unix_timestamp);
+        let release = listing.proceeds_release;
+        if let Some(proceeds_escrow) = ctx.accounts.proceeds_escrow.as_mut() {
+            proceeds_escrow.record_sale(release, creator_received, true, clock.unix_timestamp)?;
+        }
+    }
+
+    // Update platform stats
+    let config = &mut ctx.accounts.platform_config;
+    config.total_fees_collected = config
+        .total_fees_collected
+        .checked_add(fee_received)
+        .ok_or(RoyaltiesError::Overflow)?;
+
+    emit!(AuctionSettled {
+        auction: ctx.accounts.auction.key(),
+        royalty_listing: royalty_listing_key,
+        winner: ctx.accounts.winner.key(),
+        amount,
+        price,
+        platform_fee: split.platform_fee,
+        creator_amount: split.creator_amount,
+        refunded,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{RoyaltyListing, ResaleListing, PayoutPool, PayoutClaim};
+use crate::state::{ROYALTY_LISTING_SEED, RESALE_LISTING_SEED, PAYOUT_POOL_SEED, PAYOUT_CLAIM_SEED};
+use crate::events::PayoutClaimSynced;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct SyncPayoutClaim<'info> {
+    /// Anyone may sync a position; pays for it if the holder has none yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: only used as a seed and to derive the holder's share account
+    pub holder: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    #[account(
+        mut,
+        seeds = [PAYOUT_POOL_SEED, royalty_listing.key().as_ref()],
+        bump = payout_pool.bump
+    )]
+    pub payout_pool: Account<'info, PayoutPool>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PayoutClaim::LEN,
+        seeds = [PAYOUT_CLAIM_SEED, payout_pool.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub payout_claim: Account<'info, PayoutClaim>,
+
+    /// CHECK: the holder's associated share account, read by `held_shares`
+    #[account(
+        address = get_associated_token_address_with_program_id(
+            &holder.key(),
+            &royalty_listing.nft_mint,
+            &token_2022::ID,
+        ) @ RoyaltiesError::NotOwner
+    )]
+    pub holder_nft: UncheckedAccount<'info>,
+
+    /// CHECK: the holder's resale listing PDA, read by `held_shares` if it exists
+    #[account(
+        seeds = [RESALE_LISTING_SEED, royalty_listing.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub resale_listing: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Shares backed by tokens: the holder's associated share account plus
+/// any shares escrowed in their resale listing.
+pub fn held_shares(holder_nft: &AccountInfo, resale_listing: &AccountInfo) -> Result<u64> {
+    let mut held = 0;
+    if !holder_nft.data_is_empty() {
+        require_keys_eq!(*holder_nft.owner, token_2022::ID, RoyaltiesError::NotOwner);
+        let account = TokenAccount::try_deserialize(&mut &holder_nft.data.borrow()[..])?;
+        held = account.amount;
+    }
+    if !resale_listing.data_is_empty() {
+        require_keys_eq!(*resale_listing.owner, crate::ID, RoyaltiesError::NotOwner);
+        let resale = ResaleListing::try_deserialize(&mut &resale_listing.data.borrow()[..])?;
+        held = held
+            .checked_add(resale.amount)
+            .ok_or(RoyaltiesError::Overflow)?;
+    }
+    Ok(held)
+}
+
+pub fn handler(ctx: Context<SyncPayoutClaim>) -> Result<()> {
+    let holder = ctx.accounts.holder.key();
+    let claim = &mut ctx.accounts.payout_claim;
+    claim.payout_pool = ctx.accounts.payout_pool.key();
+    claim.holder = holder;
+    claim.bump = ctx.bumps.payout_claim;
+
+    let held = held_shares(
+        &ctx.accounts.holder_nft.to_account_info(),
+        &ctx.accounts.resale_listing.to_account_info(),
+    )?;
+    let pool = &mut ctx.accounts.payout_pool;
+    let (released, taken) = claim.reconcile(held, pool)?;
+
+    emit!(PayoutClaimSynced {
+        payout_pool: pool.key(),
+        royalty_listing: ctx.accounts.royalty_listing.key(),
+        holder,
+        shares: claim.shares,
+        released,
+        taken,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
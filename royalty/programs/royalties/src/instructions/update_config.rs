@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::state::PlatformConfig;
+use crate::state::PLATFORM_CONFIG_SEED;
+use crate::events::ConfigUpdated;
+use crate::errors::RoyaltiesError;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateConfigArgs {
+    /// New primary market fee in basis points
+    pub platform_fee_bps: Option<u16>,
+    /// New secondary market fee in basis points
+    pub secondary_fee_bps: Option<u16>,
+    /// New treasury for collecting fees
+    pub treasury: Option<Pubkey>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_CONFIG_SEED],
+        bump = platform_config.bump,
+        has_one = authority @ RoyaltiesError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<UpdateConfig>, args: UpdateConfigArgs) -> Result<()> {
+    let config = &mut ctx.accounts.platform_config;
+
+    if let Some(platform_fee_bps) = args.platform_fee_bps {
+        // Max 10% fee
+        require!(platform_fee_bps <= 1000, RoyaltiesError::FeeTooHigh);
+        config.platform_fee_bps = platform_fee_bps;
+    }
+
+    if let Some(secondary_fee_bps) = args.secondary_fee_bps {
+        // Max 10% fee
+        require!(secondary_fee_bps <= 1000, RoyaltiesError::FeeTooHigh);
+        config.secondary_fee_bps = secondary_fee_bps;
+    }
+
+    if let Some(treasury) = args.treasury {
+        config.treasury = treasury;
+    }
+
+    emit!(ConfigUpdated {
+        platform_config: config.key(),
+        authority: config.authority,
+        platform_fee_bps: config.platform_fee_bps,
+        secondary_fee_bps: config.secondary_fee_bps,
+        treasury: config.treasury,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::mpl_token_metadata::types::DataV2;
+use anchor_spl::metadata::{self, Metadata, MetadataAccount, UpdateMetadataAccountsV2};
+use crate::state::{RoyaltyListing, ListingStatus, SaleMode, Auction, ROYALTY_NFT_SYMBOL};
+use crate::state::{ROYALTY_LISTING_SEED, AUCTION_SEED};
+use crate::events::ListingUpdated;
+use crate::errors::RoyaltiesError;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateListingArgs {
+    /// New price per share in base units of the payment mint (fixed-price listings only)
+    pub price: Option<u64>,
+    /// Allow or forbid resale (unsold listings only)
+    pub resale_allowed: Option<bool>,
+    /// New creator royalty on resales in basis points (unsold listings only)
+    pub creator_royalty_bps: Option<u16>,
+    /// New metadata URI
+    pub metadata_uri: Option<String>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateListing<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ROYALTY_LISTING_SEED, creator.key().as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump,
+        constraint = royalty_listing.status == ListingStatus::Active @ RoyaltiesError::ListingNotActive,
+        constraint = royalty_listing.creator == creator.key() @ RoyaltiesError::Unauthorized
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    /// Bid state of an English auction listing (omitted for other listings)
+    #[account(
+        seeds = [AUCTION_SEED, royalty_listing.key().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Option<Account<'info, Auction>>,
+
+    /// Metaplex metadata of the NFT mint, kept in sync with the listing
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), royalty_listing.nft_mint.as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+pub fn handler(ctx: Context<UpdateListing>, args: UpdateListingArgs) -> Result<()> {
+    // Bidders escrowed funds under the current terms and can't withdraw
+    if let SaleMode::EnglishAuction { .. } = ctx.accounts.royalty_listing.sale_mode {
+        let auction = ctx
+            .accounts
+            .auction
+            .as_ref()
+            .ok_or(RoyaltiesError::InvalidAuction)?;
+        require!(auction.highest_bidder.is_none(), RoyaltiesError::AuctionHasBids);
+    }
+
+    let listing = &mut ctx.accounts.royalty_listing;
+    let old_price = listing.price;
+    let old_resale_allowed = listing.resale_allowed;
+    let old_creator_royalty_bps = listing.creator_royalty_bps;
+    let old_metadata_uri = listing.metadata_uri.clone();
+
+    if let Some(price) = args.price {
+        // Auctions price themselves from the sale mode, never from `price`
+        require!(
+            matches!(listing.sale_mode, SaleMode::FixedPrice),
+            RoyaltiesError::SoldByAuction
+        );
+        listing.price = price;
+    }
+    if let Some(metadata_uri) = args.metadata_uri {
+        listing.metadata_uri = metadata_uri;
+    }
+    // Holders bought under the resale terms, so those are fixed after the first sale
+    if args.resale_allowed.is_some() || args.creator_royalty_bps.is_some() {
+        require!(listing.shares_sold == 0, RoyaltiesError::SharesAlreadySold);
+    }
+    if let Some(resale_allowed) = args.resale_allowed {
+        listing.resale_allowed = resale_allowed;
+    }
+    if let Some(creator_royalty_bps) = args.creator_royalty_bps {
+        listing.creator_royalty_bps = creator_royalty_bps;
+    }
+    listing.validate_terms()?;
+
+    // Mirror the URI and resale royalty into the Metaplex metadata
+    if listing.metadata_uri != old_metadata_uri
+        || listing.creator_royalty_bps != old_creator_royalty_bps
+    {
+        let creator_key = ctx.accounts.creator.key();
+        let seeds = &[
+            ROYALTY_LISTING_SEED,
+            creator_key.as_ref(),
+            listing.nft_mint.as_ref(),
+            &[listing.bump],
+        ];
+        let metadata = &ctx.accounts.metadata;
+        metadata::update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: metadata.to_account_info(),
+                    update_authority: listing.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            None,
+            Some(DataV2 {
+                name: listing.nft_name(),
+                symbol: ROYALTY_NFT_SYMBOL.to_string(),
+                uri: listing.metadata_uri.clone(),
+                seller_fee_basis_points: listing.creator_royalty_bps,
+                creators: metadata.creators.clone(),
+                collection: metadata.collection.clone(),
+                uses: metadata.uses.clone(),
+            }),
+            None,
+            None,
+        )?;
+    }
+
+    let listing = &ctx.accounts.royalty_listing;
+    emit!(ListingUpdated {
+        royalty_listing: listing.key(),
+        creator: listing.creator,
+        nft_mint: listing.nft_mint,
+        old_price,
+        new_price: listing.price,
+        old_resale_allowed,
+        new_resale_allowed: listing.resale_allowed,
+        old_creator_royalty_bps,
+        new_creator_royalty_bps: listing.creator_royalty_bps,
+        old_metadata_uri,
+        new_metadata_uri: listing.metadata_uri.clone(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::{RoyaltyListing, ResaleListing};
+use crate::state::{ROYALTY_LISTING_SEED, RESALE_LISTING_SEED};
+use crate::events::ResalePriceUpdated;
+use crate::errors::RoyaltiesError;
+
+#[derive(Accounts)]
+pub struct UpdateResalePrice<'info> {
+    pub seller: Signer<'info>,
+
+    #[account(
+        seeds = [ROYALTY_LISTING_SEED, royalty_listing.creator.as_ref(), royalty_listing.nft_mint.as_ref()],
+        bump = royalty_listing.bump
+    )]
+    pub royalty_listing: Account<'info, RoyaltyListing>,
+
+    #[account(
+        mut,
+        seeds = [RESALE_LISTING_SEED, royalty_listing.key().as_ref(), seller.key().as_ref()],
+        bump = resale_listing.bump,
+        constraint = resale_listing.seller == seller.key() @ RoyaltiesError::Unauthorized
+    )]
+    pub resale_listing: Account<'info, ResaleListing>,
+}
+
+pub fn handler(ctx: Context<UpdateResalePrice>, price: u64) -> Result<()> {
+    require!(price > 0, RoyaltiesError::InvalidPrice);
+
+    let clock = Clock::get()?;
+    let resale = &mut ctx.accounts.resale_listing;
+    require!(!resale.is_expired(clock.unix_timestamp), RoyaltiesError::ResaleExpired);
+    let old_price = resale.price;
+    resale.price = price;
+    resale.updated_at = clock.unix_timestamp;
+
+    emit!(ResalePriceUpdated {
+        resale_listing: resale.key(),
+        royalty_listing: resale.royalty_listing,
+        seller: resale.seller,
+        old_price,
+        new_price: price,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod payments;
 pub mod state;
 
 use instructions::*;
@@ -17,6 +19,39 @@ pub mod royalties {
         instructions::initialize::handler(ctx, platform_fee_bps)
     }
 
+    /// Update platform fees and treasury (authority only)
+    pub fn update_config(ctx: Context<UpdateConfig>, args: UpdateConfigArgs) -> Result<()> {
+        instructions::update_config::handler(ctx, args)
+    }
+
+    /// Propose a new platform authority (authority only)
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_authority::handler(ctx, new_authority)
+    }
+
+    /// Pending authority accepts and takes over the platform
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority::handler(ctx)
+    }
+
+    /// Allow listings to be priced and paid out in a mint (authority only)
+    pub fn add_payment_mint(ctx: Context<AddPaymentMint>) -> Result<()> {
+        instructions::add_payment_mint::handler(ctx)
+    }
+
+    /// Stop new listings from using a payment mint (authority only)
+    pub fn remove_payment_mint(ctx: Context<RemovePaymentMint>, payment_mint: Pubkey) -> Result<()> {
+        instructions::remove_payment_mint::handler(ctx, payment_mint)
+    }
+
+    /// Create the platform collection royalty NFTs are verified into (authority only)
+    pub fn create_collection(
+        ctx: Context<CreateCollection>,
+        args: CreateCollectionArgs,
+    ) -> Result<()> {
+        instructions::create_collection::handler(ctx, args)
+    }
+
     /// Creator lists royalties for sale - mints NFT representing ownership
     pub fn create_listing(
         ctx: Context<CreateListing>,
@@ -25,22 +60,69 @@ pub mod royalties {
         instructions::create_listing::handler(ctx, args)
     }
 
-    /// Buyer purchases a royalty listing from primary market
-    pub fn buy_listing(ctx: Context<BuyListing>) -> Result<()> {
-        instructions::buy_listing::handler(ctx)
+    /// Creator changes the price or terms of an active listing
+    pub fn update_listing(ctx: Context<UpdateListing>, args: UpdateListingArgs) -> Result<()> {
+        instructions::update_listing::handler(ctx, args)
+    }
+
+    /// Creator cancels an unsold primary listing
+    pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
+        instructions::cancel_listing::handler(ctx)
+    }
+
+    /// Creator ends the primary sale of a partly sold listing, keeping the shares sold
+    pub fn close_primary_sale(ctx: Context<ClosePrimarySale>) -> Result<()> {
+        instructions::close_primary_sale::handler(ctx)
+    }
+
+    /// Mark a listing whose term has ended as expired (permissionless)
+    pub fn expire_listing(ctx: Context<ExpireListing>) -> Result<()> {
+        instructions::expire_listing::handler(ctx)
+    }
+
+    /// Buyer purchases shares of a royalty listing from primary market
+    pub fn buy_listing(
+        ctx: Context<BuyListing>,
+        amount: u64,
+        expected_price: u64,
+        max_total_cost: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::buy_listing::handler(ctx, amount, expected_price, max_total_cost, proof)
+    }
+
+    /// Escrow a bid for the whole supply of an English auction listing
+    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+        instructions::place_bid::handler(ctx, amount)
+    }
+
+    /// Mint an ended auction's shares to the winner and pay out the bid (permissionless)
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        instructions::settle_auction::handler(ctx)
     }
 
-    /// List a royalty token for resale on secondary market
+    /// List royalty shares for resale on secondary market
     pub fn list_for_resale(
         ctx: Context<ListForResale>,
         price: u64,
+        amount: u64,
+        expires_at: Option<i64>,
     ) -> Result<()> {
-        instructions::list_for_resale::handler(ctx, price)
+        instructions::list_for_resale::handler(ctx, price, amount, expires_at)
     }
 
-    /// Buy a royalty token from secondary market
-    pub fn buy_resale(ctx: Context<BuyResale>) -> Result<()> {
-        instructions::buy_resale::handler(ctx)
+    /// Change the price of a resale listing in place
+    pub fn update_resale_price(ctx: Context<UpdateResalePrice>, price: u64) -> Result<()> {
+        instructions::update_resale_price::handler(ctx, price)
+    }
+
+    /// Buy royalty shares from secondary market
+    pub fn buy_resale(
+        ctx: Context<BuyResale>,
+        expected_price: u64,
+        max_total_cost: u64,
+    ) -> Result<()> {
+        instructions::buy_resale::handler(ctx, expected_price, max_total_cost)
     }
 
     /// Cancel a resale listing
@@ -48,6 +130,30 @@ pub mod royalties {
         instructions::cancel_resale::handler(ctx)
     }
 
+    /// Return an expired resale listing's shares to its seller (permissionless)
+    pub fn close_expired_resale(ctx: Context<CloseExpiredResale>) -> Result<()> {
+        instructions::close_expired_resale::handler(ctx)
+    }
+
+    /// Escrow a bid for royalty shares on the secondary market
+    pub fn make_offer(ctx: Context<MakeOffer>, amount: u64, price: u64) -> Result<()> {
+        instructions::make_offer::handler(ctx, amount, price)
+    }
+
+    /// Withdraw a bid and refund its escrow
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        instructions::cancel_offer::handler(ctx)
+    }
+
+    /// Sell held shares into a bid
+    pub fn accept_offer(
+        ctx: Context<AcceptOffer>,
+        expected_price: u64,
+        min_proceeds: u64,
+    ) -> Result<()> {
+        instructions::accept_offer::handler(ctx, expected_price, min_proceeds)
+    }
+
     /// Creator deposits payout for royalty holders
     pub fn deposit_payout(ctx: Context<DepositPayout>, amount: u64) -> Result<()> {
         instructions::deposit_payout::handler(ctx, amount)
@@ -57,5 +163,25 @@ pub mod royalties {
     pub fn claim_payout(ctx: Context<ClaimPayout>) -> Result<()> {
         instructions::claim_payout::handler(ctx)
     }
+
+    /// Reconcile a holder's payout position with the shares they hold (permissionless)
+    pub fn sync_payout_claim(ctx: Context<SyncPayoutClaim>) -> Result<()> {
+        instructions::sync_payout_claim::handler(ctx)
+    }
+
+    /// Creator withdraws escrowed sale proceeds unlocked so far
+    pub fn release_proceeds(ctx: Context<ReleaseProceeds>) -> Result<()> {
+        instructions::release_proceeds::handler(ctx)
+    }
+
+    /// Pay escrowed proceeds the creator can no longer unlock out to holders (permissionless)
+    pub fn forfeit_proceeds(ctx: Context<ForfeitProceeds>) -> Result<()> {
+        instructions::forfeit_proceeds::handler(ctx)
+    }
+
+    /// Flag a sold-out listing whose scheduled payout is overdue (permissionless)
+    pub fn mark_delinquent(ctx: Context<MarkDelinquent>) -> Result<()> {
+        instructions::mark_delinquent::handler(ctx)
+    }
 }
 
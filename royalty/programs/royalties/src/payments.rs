@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::errors::RoyaltiesError;
+
+/// Payer side of a transfer in a listing's payment mint.
+///
+/// SOL listings move lamports with system transfers; every other listing moves
+/// tokens with `transfer_checked` under the Token or Token-2022 program.
+pub struct Payment<'a, 'info> {
+    /// Whether the listing is priced in native SOL
+    pub sol: bool,
+    pub payer: &'a Signer<'info>,
+    /// Payer's token account (None for SOL listings)
+    pub from: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    /// Payment mint (None for SOL listings)
+    pub mint: Option<&'a InterfaceAccount<'info, Mint>>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+    pub system_program: &'a Program<'info, System>,
+}
+
+impl<'info> Payment<'_, 'info> {
+    /// Pay `amount` to `recipient`, or to its token account `to` for token listings.
+    ///
+    /// Returns the amount the recipient actually received, which is less than
+    /// `amount` when a Token-2022 transfer fee is withheld.
+    pub fn pay(
+        &self,
+        recipient: AccountInfo<'info>,
+        to: Option<&InterfaceAccount<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<u64> {
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        if self.sol {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.payer.to_account_info(),
+                        to: recipient,
+                    },
+                ),
+                amount,
+            )?;
+            return Ok(amount);
+        }
+
+        let from = self.from.ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        let to = to.ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        let mint = self.mint.ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        transfer_reconciled(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.payer.to_account_info(),
+                },
+            ),
+            amount,
+            mint.decimals,
+        )
+    }
+}
+
+/// Program-owned escrow paying out in a listing's payment mint.
+///
+/// SOL escrows hold lamports in the `authority` PDA itself; token escrows hold
+/// them in `vault`, a token account owned by that PDA.
+pub struct Escrow<'a, 'info> {
+    /// Whether the listing is priced in native SOL
+    pub sol: bool,
+    /// PDA owning the escrowed funds
+    pub authority: AccountInfo<'info>,
+    pub signer_seeds: &'a [&'a [&'a [u8]]],
+    /// Escrow token account (None for SOL listings)
+    pub vault: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    /// Payment mint (None for SOL listings)
+    pub mint: Option<&'a InterfaceAccount<'info, Mint>>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'info> Escrow<'_, 'info> {
+    /// Pay `amount` out of escrow to `recipient`, or to its token account `to`
+    /// for token listings. Returns the amount the recipient actually received.
+    pub fn pay(
+        &self,
+        recipient: AccountInfo<'info>,
+        to: Option<&InterfaceAccount<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<u64> {
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        if self.sol {
+            withdraw_lamports(&self.authority, &recipient, amount)?;
+            return Ok(amount);
+        }
+
+        let vault = self.vault.ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        let to = to.ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        let mint = self.mint.ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        transfer_reconciled(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: vault.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.authority.clone(),
+                },
+                self.signer_seeds,
+            ),
+            amount,
+            mint.decimals,
+        )
+    }
+
+    /// Sweep whatever is left in the token vault to `leftover_to` and close the
+    /// vault to `destination`. SOL escrows are closed with their PDA instead.
+    pub fn close_vault(
+        &self,
+        leftover_to: Option<&InterfaceAccount<'info, TokenAccount>>,
+        destination: AccountInfo<'info>,
+    ) -> Result<()> {
+        if self.sol {
+            return Ok(());
+        }
+
+        let vault = self.vault.ok_or(RoyaltiesError::PaymentAccountMissing)?;
+        // Tokens sent to the vault by anyone else must not block the close
+        let leftover = token_balance(&vault.to_account_info())?;
+        self.pay(destination.clone(), leftover_to, leftover)?;
+
+        token_interface::close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: vault.to_account_info(),
+                destination,
+                authority: self.authority.clone(),
+            },
+            self.signer_seeds,
+        ))
+    }
+}
+
+/// Platform fee and creator proceeds of a primary market sale
+pub struct PrimarySplit {
+    pub platform_fee: u64,
+    pub creator_amount: u64,
+}
+
+impl PrimarySplit {
+    pub fn new(price: u64, platform_fee_bps: u16) -> Result<Self> {
+        let platform_fee = price
+            .checked_mul(platform_fee_bps as u64)
+            .ok_or(RoyaltiesError::Overflow)?
+            .checked_div(10000)
+            .ok_or(RoyaltiesError::Overflow)?;
+        let creator_amount = price
+            .checked_sub(platform_fee)
+            .ok_or(RoyaltiesError::Overflow)?;
+
+        Ok(Self {
+            platform_fee,
+            creator_amount,
+        })
+    }
+}
+
+/// Platform fee, creator royalty and seller proceeds of a secondary market sale
+pub struct ResaleSplit {
+    pub platform_fee: u64,
+    pub creator_royalty: u64,
+    pub seller_amount: u64,
+}
+
+impl ResaleSplit {
+    pub fn new(price: u64, secondary_fee_bps: u16, creator_royalty_bps: u16) -> Result<Self> {
+        let platform_fee = price
+            .checked_mul(secondary_fee_bps as u64)
+            .ok_or(RoyaltiesError::Overflow)?
+            .checked_div(10000)
+            .ok_or(RoyaltiesError::Overflow)?;
+
+        let creator_royalty = price
+            .checked_mul(creator_royalty_bps as u64)
+            .ok_or(RoyaltiesError::Overflow)?
+            .checked_div(10000)
+            .ok_or(RoyaltiesError::Overflow)?;
+
+        let seller_amount = price
+            .checked_sub(platform_fee)
+            .ok_or(RoyaltiesError::Overflow)?
+            .checked_sub(creator_royalty)
+            .ok_or(RoyaltiesError::Overflow)?;
+
+        Ok(Self {
+            platform_fee,
+            creator_royalty,
+            seller_amount,
+        })
+    }
+}
+
+/// `transfer_checked` that returns the amount credited to the destination
+pub fn transfer_reconciled<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<u64> {
+    let to = ctx.accounts.to.clone();
+    let before = token_balance(&to)?;
+    token_interface::transfer_checked(ctx, amount, decimals)?;
+    let received = token_balance(&to)?
+        .checked_sub(before)
+        .ok_or(RoyaltiesError::Overflow)?;
+    Ok(received)
+}
+
+/// Current balance of a Token or Token-2022 account
+fn token_balance(account: &AccountInfo) -> Result<u64> {
+    let data = account.try_borrow_data()?;
+    Ok(TokenAccount::try_deserialize(&mut &data[..])?.amount)
+}
+
+/// Move lamports out of a program-owned account (e.g. a SOL payout pool)
+pub fn withdraw_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let remaining = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(RoyaltiesError::InsufficientFunds)?;
+    let received = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(RoyaltiesError::Overflow)?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = received;
+    Ok(())
+}
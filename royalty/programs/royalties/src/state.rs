@@ -1,4 +1,30 @@
 use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+use anchor_spl::token::spl_token::native_mint;
+use crate::errors::RoyaltiesError;
+
+/// PDA seed for `PlatformConfig`
+pub const PLATFORM_CONFIG_SEED: &[u8] = b"platform_config";
+/// PDA seed for `RoyaltyListing` (with creator and NFT mint)
+pub const ROYALTY_LISTING_SEED: &[u8] = b"royalty_listing";
+/// PDA seed for `ResaleListing` (with royalty listing and seller)
+pub const RESALE_LISTING_SEED: &[u8] = b"resale_listing";
+/// PDA seed for `PayoutPool` (with royalty listing)
+pub const PAYOUT_POOL_SEED: &[u8] = b"payout_pool";
+/// PDA seed for `PayoutClaim` (with payout pool and holder)
+pub const PAYOUT_CLAIM_SEED: &[u8] = b"payout_claim";
+/// PDA seed for `Offer` (with royalty listing and bidder)
+pub const OFFER_SEED: &[u8] = b"offer";
+/// PDA seed for `Auction` (with royalty listing)
+pub const AUCTION_SEED: &[u8] = b"auction";
+/// PDA seed for `ProceedsEscrow` (with royalty listing)
+pub const PROCEEDS_ESCROW_SEED: &[u8] = b"proceeds_escrow";
+
+/// Maximum number of allowlisted payment mints
+pub const MAX_PAYMENT_MINTS: usize = 8;
+
+/// Metaplex symbol of every royalty NFT
+pub const ROYALTY_NFT_SYMBOL: &str = "ROYALTY";
 
 /// Platform configuration account
 #[account]
@@ -6,6 +32,8 @@ use anchor_lang::prelude::*;
 pub struct PlatformConfig {
     /// Platform authority (admin)
     pub authority: Pubkey,
+    /// Proposed new authority, takes over once it accepts
+    pub pending_authority: Option<Pubkey>,
     /// Platform treasury for collecting fees
     pub treasury: Pubkey,
     /// Platform fee in basis points (e.g., 500 = 5%)
@@ -14,6 +42,10 @@ pub struct PlatformConfig {
     pub secondary_fee_bps: u16,
     /// Total fees collected (for tracking)
     pub total_fees_collected: u64,
+    /// Mints listings may be priced and paid out in (e.g. USDC, USDT, PYUSD)
+    pub payment_mints: Vec<Pubkey>,
+    /// Platform collection every royalty NFT is verified into (None until created)
+    pub collection_mint: Option<Pubkey>,
     /// Bump seed for PDA
     pub bump: u8,
 }
@@ -21,11 +53,19 @@ pub struct PlatformConfig {
 impl PlatformConfig {
     pub const LEN: usize = 8 + // discriminator
         32 + // authority
+        1 + 32 + // pending_authority
         32 + // treasury
         2 +  // platform_fee_bps
         2 +  // secondary_fee_bps
         8 +  // total_fees_collected
+        4 + 32 * MAX_PAYMENT_MINTS + // payment_mints
+        1 + 32 + // collection_mint
         1;   // bump
+
+    /// Whether listings may use `mint` for payments
+    pub fn is_payment_mint_allowed(&self, mint: &Pubkey) -> bool {
+        self.payment_mints.contains(mint)
+    }
 }
 
 /// Royalty listing account - represents a creator's royalty offering
@@ -33,8 +73,10 @@ impl PlatformConfig {
 pub struct RoyaltyListing {
     /// Creator's wallet address
     pub creator: Pubkey,
-    /// NFT mint address
+    /// Share mint address, always a Token-2022 mint
     pub nft_mint: Pubkey,
+    /// Mint accepted for purchases and payouts (native mint = SOL)
+    pub payment_mint: Pubkey,
     /// Revenue source description (stored as hash, full data on IPFS)
     pub metadata_uri: String,
     /// Percentage of royalties being sold (basis points, e.g., 500 = 5%)
@@ -43,8 +85,20 @@ pub struct RoyaltyListing {
     pub duration_seconds: u64,
     /// Start timestamp
     pub start_timestamp: i64,
-    /// Price in USDC (6 decimals)
+    /// Price per share in base units of the payment mint (lamports for SOL)
     pub price: u64,
+    /// How the primary shares are sold
+    pub sale_mode: SaleMode,
+    /// Allowlist-only window at the start of the primary sale
+    pub presale: Option<Presale>,
+    /// How primary sale proceeds reach the creator
+    pub proceeds_release: ProceedsRelease,
+    /// Flagged by `mark_delinquent` while a scheduled payout is overdue
+    pub delinquent: bool,
+    /// Total number of shares issued (1 = single NFT)
+    pub total_shares: u64,
+    /// Shares sold on the primary market
+    pub shares_sold: u64,
     /// Whether resale is allowed
     pub resale_allowed: bool,
     /// Creator's royalty on resales (basis points)
@@ -59,29 +113,209 @@ impl RoyaltyListing {
     pub const LEN: usize = 8 + // discriminator
         32 + // creator
         32 + // nft_mint
+        32 + // payment_mint
         4 + 200 + // metadata_uri (max 200 chars)
         2 +  // percentage_bps
         8 +  // duration_seconds
         8 +  // start_timestamp
         8 +  // price
+        1 + 8 + 8 + 8 + 8 + // sale_mode (largest variant)
+        1 + 32 + 8 + // presale
+        1 + 2 + 8 + 8 + // proceeds_release (largest variant)
+        1 +  // delinquent
+        8 +  // total_shares
+        8 +  // shares_sold
         1 +  // resale_allowed
         2 +  // creator_royalty_bps
         1 +  // status
         1;   // bump
+
+    /// Whether the listing is priced and paid out in native SOL (lamports)
+    pub fn pays_in_sol(&self) -> bool {
+        self.payment_mint == native_mint::ID
+    }
+
+    /// Validate the creator-adjustable terms: price, metadata URI and resale royalty
+    pub fn validate_terms(&self) -> Result<()> {
+        match self.sale_mode {
+            SaleMode::FixedPrice => {
+                require!(self.price > 0, RoyaltiesError::InvalidPrice);
+                // Full supply must be purchasable without overflowing the total price
+                self.price
+                    .checked_mul(self.total_shares)
+                    .ok_or(RoyaltiesError::Overflow)?;
+            }
+            SaleMode::EnglishAuction { reserve_price, .. } => {
+                require!(reserve_price > 0, RoyaltiesError::InvalidPrice);
+            }
+            SaleMode::DutchAuction { start_price, floor_price, decay_period, .. } => {
+                require!(
+                    floor_price > 0 && start_price > floor_price,
+                    RoyaltiesError::InvalidPrice
+                );
+                require!(decay_period > 0, RoyaltiesError::InvalidDecayPeriod);
+                start_price
+                    .checked_mul(self.total_shares)
+                    .ok_or(RoyaltiesError::Overflow)?;
+            }
+        }
+        match self.proceeds_release {
+            ProceedsRelease::Immediate => {}
+            ProceedsRelease::LinearVesting { period } => {
+                require!(period > 0, RoyaltiesError::InvalidProceedsRelease);
+            }
+            ProceedsRelease::PayoutTranches { count, interval, .. } => {
+                require!(count > 0 && interval > 0, RoyaltiesError::InvalidProceedsRelease);
+                // Deposits stop at the end of the term, so every tranche must fall within it
+                let schedule = u64::from(count)
+                    .checked_mul(interval)
+                    .ok_or(RoyaltiesError::Overflow)?;
+                require!(
+                    self.duration_seconds == 0 || schedule <= self.duration_seconds,
+                    RoyaltiesError::InvalidProceedsRelease
+                );
+            }
+        }
+        require!(
+            !self.metadata_uri.is_empty() && self.metadata_uri.len() <= 200,
+            RoyaltiesError::InvalidMetadataUri
+        );
+        require!(
+            self.creator_royalty_bps <= 1000, // Max 10% creator royalty on resales
+            RoyaltiesError::FeeTooHigh
+        );
+        Ok(())
+    }
+
+    /// Metaplex name of the royalty NFT, e.g. "Royalty 5% – 8iLs…TBfg"
+    ///
+    /// The creator is abbreviated to fit the 32-byte Metaplex name limit.
+    pub fn nft_name(&self) -> String {
+        let whole = self.percentage_bps / 100;
+        let fraction = self.percentage_bps % 100;
+        let percentage = match fraction {
+            0 => format!("{whole}%"),
+            f if f % 10 == 0 => format!("{whole}.{}%", f / 10),
+            f => format!("{whole}.{f:02}%"),
+        };
+        let creator = self.creator.to_string();
+        format!(
+            "Royalty {percentage} – {}…{}",
+            &creator[..4],
+            &creator[creator.len() - 4..]
+        )
+    }
+
+    /// Per-share price `buy_listing` charges at `now`
+    ///
+    /// Dutch auctions fall linearly from `start_price` at `start_time` to
+    /// `floor_price` once `decay_period` seconds have passed.
+    pub fn current_price(&self, now: i64) -> Result<u64> {
+        match self.sale_mode {
+            SaleMode::FixedPrice => Ok(self.price),
+            SaleMode::EnglishAuction { .. } => err!(RoyaltiesError::SoldByAuction),
+            SaleMode::DutchAuction { start_price, floor_price, start_time, decay_period } => {
+                require!(now >= start_time, RoyaltiesError::AuctionNotStarted);
+                let elapsed = now.abs_diff(start_time);
+                if elapsed >= decay_period {
+                    return Ok(floor_price);
+                }
+                let decay = u128::from(start_price - floor_price) * u128::from(elapsed)
+                    / u128::from(decay_period);
+                // decay < start_price - floor_price, so this can't underflow
+                Ok(start_price - decay as u64)
+            }
+        }
+    }
+
+    /// When the royalty term ends (None for perpetual listings)
+    pub fn expires_at(&self) -> Option<i64> {
+        if self.duration_seconds == 0 {
+            return None;
+        }
+        let duration = i64::try_from(self.duration_seconds).unwrap_or(i64::MAX);
+        Some(self.start_timestamp.saturating_add(duration))
+    }
+
+    /// Whether the royalty term has ended (perpetual listings never expire)
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at().is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+/// How a listing's primary shares are sold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SaleMode {
+    /// Any number of shares at `price` each through `buy_listing`
+    #[default]
+    FixedPrice,
+    /// The whole share supply goes to the highest bid for the lot, at least
+    /// `reserve_price`, once `end_time` has passed
+    EnglishAuction { reserve_price: u64, end_time: i64 },
+    /// Any number of shares through `buy_listing` at a per-share price falling
+    /// from `start_price` at `start_time` to `floor_price` over `decay_period` seconds
+    DutchAuction {
+        start_price: u64,
+        floor_price: u64,
+        start_time: i64,
+        decay_period: u64,
+    },
 }
 
+/// How the creator receives primary sale proceeds
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProceedsRelease {
+    /// Paid to the creator at each sale
+    #[default]
+    Immediate,
+    /// Escrowed, then released linearly over `period` seconds from the first sale
+    LinearVesting { period: u64 },
+    /// Escrowed, with one of `count` equal tranches unlocked by each on-time
+    /// `deposit_payout` of at least `min_deposit`, due every `interval` seconds
+    /// once the listing sells out
+    PayoutTranches { count: u16, interval: u64, min_deposit: u64 },
+}
+
+/// Allowlist window during which only listed wallets can `buy_listing`
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum ListingStatus {
-    Active,    // Available for purchase
-    Sold,      // Purchased, NFT minted
-    Cancelled, // Creator cancelled
-    Expired,   // Duration ended
+pub struct Presale {
+    /// Merkle root over `presale_leaf` of every allowlisted wallet
+    pub merkle_root: [u8; 32],
+    /// Unix timestamp at which the listing opens to everyone
+    pub ends_at: i64,
 }
 
-impl Default for ListingStatus {
-    fn default() -> Self {
-        ListingStatus::Active
+impl Presale {
+    pub fn is_open(&self, now: i64) -> bool {
+        now < self.ends_at
     }
+
+    /// Whether `proof` links `buyer`'s leaf to the merkle root
+    pub fn is_allowlisted(&self, buyer: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        let node = proof
+            .iter()
+            .fold(presale_leaf(buyer), |node, sibling| presale_node(&node, sibling));
+        node == self.merkle_root
+    }
+}
+
+/// Merkle leaf of an allowlisted wallet
+pub fn presale_leaf(wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[wallet.as_ref()]).to_bytes()
+}
+
+/// Parent of two merkle nodes; pairs are sorted so proofs need no left/right flags
+pub fn presale_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[first, second]).to_bytes()
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListingStatus {
+    #[default]
+    Active,  // Available for purchase
+    Sold,    // All shares purchased and minted
+    Expired, // Duration ended
 }
 
 /// Secondary market listing
@@ -93,10 +327,16 @@ pub struct ResaleListing {
     pub royalty_listing: Pubkey,
     /// NFT mint
     pub nft_mint: Pubkey,
-    /// Asking price in USDC
+    /// Number of shares held in escrow
+    pub amount: u64,
+    /// Asking price in the payment mint for all escrowed shares
     pub price: u64,
     /// Listing timestamp
     pub listed_at: i64,
+    /// Last price change (listed_at until repriced)
+    pub updated_at: i64,
+    /// Unix timestamp after which the ask can no longer be filled (None = open-ended)
+    pub expires_at: Option<i64>,
     /// Bump seed
     pub bump: u8,
 }
@@ -106,11 +346,207 @@ impl ResaleListing {
         32 + // seller
         32 + // royalty_listing
         32 + // nft_mint
+        8 +  // amount
         8 +  // price
         8 +  // listed_at
+        8 +  // updated_at
+        1 + 8 + // expires_at
         1;   // bump
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
 }
 
+/// Buyer bid on the secondary market, with payment held in escrow
+#[account]
+pub struct Offer {
+    /// Buyer who posted the bid
+    pub bidder: Pubkey,
+    /// Royalty listing whose shares are wanted
+    pub royalty_listing: Pubkey,
+    /// NFT mint
+    pub nft_mint: Pubkey,
+    /// Number of shares wanted
+    pub amount: u64,
+    /// Escrowed payment for all wanted shares, net of any transfer fee
+    pub price: u64,
+    /// Offer timestamp
+    pub created_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl Offer {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // bidder
+        32 + // royalty_listing
+        32 + // nft_mint
+        8 +  // amount
+        8 +  // price
+        8 +  // created_at
+        1;   // bump
+}
+
+/// Bids on an English auction listing, escrowed in the auction's vault
+/// (or in the auction itself for SOL listings)
+#[account]
+pub struct Auction {
+    /// Royalty listing being auctioned
+    pub royalty_listing: Pubkey,
+    /// Bidder currently winning (None until the first bid)
+    pub highest_bidder: Option<Pubkey>,
+    /// Escrowed highest bid for the whole lot, net of any transfer fee
+    pub highest_bid: u64,
+    /// Number of bids placed
+    pub bid_count: u32,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl Auction {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // royalty_listing
+        1 + 32 + // highest_bidder
+        8 +  // highest_bid
+        4 +  // bid_count
+        1;   // bump
+}
+
+/// Creator proceeds of a listing with an escrowed `ProceedsRelease`, held in
+/// the escrow's vault (or in the escrow itself for SOL listings)
+#[account]
+pub struct ProceedsEscrow {
+    /// Royalty listing whose proceeds are escrowed
+    pub royalty_listing: Pubkey,
+    /// Total creator proceeds received, net of any transfer fee
+    pub total_escrowed: u64,
+    /// Total released to the creator
+    pub total_released: u64,
+    /// First escrowed sale, when linear vesting starts
+    pub vesting_start: i64,
+    /// Tranches unlocked by on-time payouts
+    pub tranches_unlocked: u16,
+    /// Deadline for the payout unlocking the next tranche (0 until sold out)
+    pub next_tranche_due: i64,
+    /// Locked proceeds forfeited to holders after the creator stopped paying out
+    pub forfeited: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl ProceedsEscrow {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // royalty_listing
+        8 +  // total_escrowed
+        8 +  // total_released
+        8 +  // vesting_start
+        2 +  // tranches_unlocked
+        8 +  // next_tranche_due
+        8 +  // forfeited
+        1;   // bump
+
+    /// Record creator proceeds received from a sale; tranche deadlines start
+    /// once the listing sells out
+    pub fn record_sale(
+        &mut self,
+        release: ProceedsRelease,
+        received: u64,
+        sold_out: bool,
+        now: i64,
+    ) -> Result<()> {
+        self.total_escrowed = self
+            .total_escrowed
+            .checked_add(received)
+            .ok_or(RoyaltiesError::Overflow)?;
+        if self.vesting_start == 0 {
+            self.vesting_start = now;
+        }
+        if let ProceedsRelease::PayoutTranches { interval, .. } = release {
+            if sold_out {
+                self.next_tranche_due = due_after(now, interval);
+            }
+        }
+        Ok(())
+    }
+
+    /// Record a payout deposit, unlocking the next tranche if it lands in the
+    /// current window and is large enough. A late deposit restarts the
+    /// schedule from now without unlocking. Returns whether a tranche unlocked.
+    pub fn record_deposit(&mut self, release: ProceedsRelease, received: u64, now: i64) -> bool {
+        let ProceedsRelease::PayoutTranches { count, interval, min_deposit } = release else {
+            return false;
+        };
+        if self.forfeited > 0 || self.tranches_unlocked >= count || received < min_deposit {
+            return false;
+        }
+        if now > self.next_tranche_due {
+            self.next_tranche_due = due_after(now, interval);
+            return false;
+        }
+        // Only one tranche per window, however many deposits land in it
+        if now <= self.next_tranche_due.saturating_sub(i64::try_from(interval).unwrap_or(i64::MAX)) {
+            return false;
+        }
+        self.tranches_unlocked += 1;
+        self.next_tranche_due = due_after(self.next_tranche_due, interval);
+        true
+    }
+
+    /// Proceeds unlocked for the creator at `now`, released or not
+    pub fn unlocked(&self, release: ProceedsRelease, now: i64) -> u64 {
+        // Forfeiture leaves the creator what had unlocked by then
+        if self.forfeited > 0 {
+            return self.total_escrowed - self.forfeited;
+        }
+        let (part, whole) = match release {
+            ProceedsRelease::Immediate => return self.total_escrowed,
+            ProceedsRelease::LinearVesting { period } => {
+                let elapsed = if self.vesting_start == 0 {
+                    0
+                } else {
+                    now.saturating_sub(self.vesting_start).max(0).unsigned_abs()
+                };
+                (elapsed.min(period), period)
+            }
+            ProceedsRelease::PayoutTranches { count, .. } => {
+                (u64::from(self.tranches_unlocked), u64::from(count))
+            }
+        };
+        // part <= whole, so the result fits in u64
+        (u128::from(self.total_escrowed) * u128::from(part) / u128::from(whole)) as u64
+    }
+
+    /// Unlocked proceeds not yet released
+    pub fn releasable(&self, release: ProceedsRelease, now: i64) -> u64 {
+        self.unlocked(release, now).saturating_sub(self.total_released)
+    }
+
+    /// Tranches still locked once the creator can no longer unlock them:
+    /// the listing's term is over, or a full interval has passed since a
+    /// tranche came due without a deposit restarting the schedule
+    pub fn forfeitable(&self, release: ProceedsRelease, expired: bool, now: i64) -> u64 {
+        let ProceedsRelease::PayoutTranches { interval, .. } = release else {
+            return 0;
+        };
+        if self.forfeited > 0 {
+            return 0;
+        }
+        let abandoned = self.next_tranche_due != 0 && now > due_after(self.next_tranche_due, interval);
+        if !expired && !abandoned {
+            return 0;
+        }
+        self.total_escrowed - self.unlocked(release, now)
+    }
+}
+
+fn due_after(from: i64, interval: u64) -> i64 {
+    from.saturating_add(i64::try_from(interval).unwrap_or(i64::MAX))
+}
+
+/// Fixed-point scale for `PayoutPool::reward_per_share`
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
 /// Payout pool for a royalty listing
 #[account]
 pub struct PayoutPool {
@@ -118,14 +554,26 @@ pub struct PayoutPool {
     pub royalty_listing: Pubkey,
     /// Creator who deposits
     pub creator: Pubkey,
-    /// Total amount deposited for current period
+    /// Total amount ever deposited
     pub total_deposited: u64,
-    /// Amount already claimed
+    /// Total amount ever claimed
     pub total_claimed: u64,
-    /// Deposit timestamp
+    /// Last deposit timestamp
     pub deposited_at: i64,
-    /// Payout period identifier
-    pub period: u64,
+    /// Cumulative payout per share, scaled by REWARD_PRECISION
+    pub reward_per_share: u128,
+    /// Committed seconds between payouts (0 = no schedule)
+    pub payout_interval_seconds: u64,
+    /// Least a deposit must pay to count as a scheduled payout
+    pub min_payout_per_period: u64,
+    /// Deadline for the next scheduled payout (0 until the first share sells)
+    pub next_payout_due: i64,
+    /// Shares released from positions whose tokens left outside the marketplace
+    pub released_shares: u64,
+    /// reward_per_share at the last settlement of the released shares
+    pub released_reward_per_share_paid: u128,
+    /// Payouts accrued on released shares, inherited by whoever takes them up
+    pub released_pending: u64,
     /// Bump seed
     pub bump: u8,
 }
@@ -137,20 +585,147 @@ impl PayoutPool {
         8 +  // total_deposited
         8 +  // total_claimed
         8 +  // deposited_at
-        8 +  // period
+        16 + // reward_per_share
+        8 +  // payout_interval_seconds
+        8 +  // min_payout_per_period
+        8 +  // next_payout_due
+        8 +  // released_shares
+        16 + // released_reward_per_share_paid
+        8 +  // released_pending
         1;   // bump
+
+    /// Start the payout schedule at the first sale: the first payout is due one
+    /// interval later. Later sales leave a running schedule alone.
+    pub fn start_schedule(&mut self, now: i64) {
+        if self.payout_interval_seconds > 0 && self.next_payout_due == 0 {
+            self.next_payout_due = now.saturating_add(self.interval());
+        }
+    }
+
+    /// Count a deposit as the payout for the current period. The schedule
+    /// advances one interval per deposit, but never more than one interval
+    /// past `now`, so a creator can catch up on missed payouts but not prepay.
+    pub fn advance_schedule(&mut self, now: i64) {
+        if self.next_payout_due == 0 {
+            return;
+        }
+        let interval = self.interval();
+        self.next_payout_due = self
+            .next_payout_due
+            .saturating_add(interval)
+            .min(now.saturating_add(interval));
+    }
+
+    /// Whether the scheduled payout is past due
+    pub fn is_overdue(&self, now: i64) -> bool {
+        self.next_payout_due != 0 && now > self.next_payout_due
+    }
+
+    fn interval(&self) -> i64 {
+        i64::try_from(self.payout_interval_seconds).unwrap_or(i64::MAX)
+    }
+
+    /// Spread a deposit across every share sold
+    pub fn record_deposit(&mut self, amount: u64, shares_sold: u64) -> Result<()> {
+        let increment = (amount as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(RoyaltiesError::Overflow)?
+            .checked_div(shares_sold as u128)
+            .ok_or(RoyaltiesError::Overflow)?;
+        self.reward_per_share = self
+            .reward_per_share
+            .checked_add(increment)
+            .ok_or(RoyaltiesError::Overflow)?;
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(RoyaltiesError::Overflow)?;
+        Ok(())
+    }
+
+    /// Amount deposited but not yet claimed
+    pub fn available(&self) -> Result<u64> {
+        Ok(self
+            .total_deposited
+            .checked_sub(self.total_claimed)
+            .ok_or(RoyaltiesError::Overflow)?)
+    }
+
+    /// Release shares no longer backed by tokens, along with the payouts
+    /// accrued on them since their holder's last settlement
+    pub fn release_shares(&mut self, shares: u64, accrued: u64) -> Result<()> {
+        self.settle_released()?;
+        self.released_shares = self
+            .released_shares
+            .checked_add(shares)
+            .ok_or(RoyaltiesError::Overflow)?;
+        self.released_pending = self
+            .released_pending
+            .checked_add(accrued)
+            .ok_or(RoyaltiesError::Overflow)?;
+        Ok(())
+    }
+
+    /// Take up to `wanted` released shares. Returns the shares taken and
+    /// their pro-rata part of the payouts accrued while they were released.
+    pub fn take_released_shares(&mut self, wanted: u64) -> Result<(u64, u64)> {
+        let taken = wanted.min(self.released_shares);
+        if taken == 0 {
+            return Ok((0, 0));
+        }
+        self.settle_released()?;
+        let inherited = (self.released_pending as u128)
+            .checked_mul(taken as u128)
+            .ok_or(RoyaltiesError::Overflow)?
+            / self.released_shares as u128;
+        let inherited = u64::try_from(inherited).map_err(|_| RoyaltiesError::Overflow)?;
+        self.released_shares -= taken;
+        self.released_pending -= inherited;
+        Ok((taken, inherited))
+    }
+
+    fn settle_released(&mut self) -> Result<()> {
+        let accrued = accrued(
+            self.released_shares,
+            self.released_reward_per_share_paid,
+            self.reward_per_share,
+        )?;
+        self.released_pending = self
+            .released_pending
+            .checked_add(accrued)
+            .ok_or(RoyaltiesError::Overflow)?;
+        self.released_reward_per_share_paid = self.reward_per_share;
+        Ok(())
+    }
+}
+
+/// Payouts accrued on `shares` while reward_per_share moved from `paid` to `current`
+fn accrued(shares: u64, paid: u128, current: u128) -> Result<u64> {
+    let delta = current.checked_sub(paid).ok_or(RoyaltiesError::Overflow)?;
+    let accrued = (shares as u128)
+        .checked_mul(delta)
+        .ok_or(RoyaltiesError::Overflow)?
+        / REWARD_PRECISION;
+    Ok(u64::try_from(accrued).map_err(|_| RoyaltiesError::Overflow)?)
 }
 
-/// Tracks claims for a specific holder in a payout period
+/// A holder's position in a payout pool
 #[account]
 pub struct PayoutClaim {
     /// The payout pool
     pub payout_pool: Pubkey,
-    /// The holder who claimed
+    /// The holder
     pub holder: Pubkey,
-    /// Amount claimed
+    /// Shares credited to the holder, reconciled against the tokens they
+    /// actually hold whenever the position is claimed or synced
+    pub shares: u64,
+    /// Pool reward_per_share at the last settlement
+    pub reward_per_share_paid: u128,
+    /// Settled payouts not yet withdrawn
+    pub pending: u64,
+    /// Total amount withdrawn
     pub amount_claimed: u64,
-    /// Claim timestamp
+    /// Last claim timestamp
     pub claimed_at: i64,
     /// Bump seed
     pub bump: u8,
@@ -160,8 +735,79 @@ impl PayoutClaim {
     pub const LEN: usize = 8 + // discriminator
         32 + // payout_pool
         32 + // holder
+        8 +  // shares
+        16 + // reward_per_share_paid
+        8 +  // pending
         8 +  // amount_claimed
         8 +  // claimed_at
         1;   // bump
-}
 
+    /// Move payouts accrued on current shares into `pending`.
+    /// Must run before `shares` changes.
+    pub fn settle(&mut self, reward_per_share: u128) -> Result<()> {
+        let accrued = accrued(self.shares, self.reward_per_share_paid, reward_per_share)?;
+        self.pending = self
+            .pending
+            .checked_add(accrued)
+            .ok_or(RoyaltiesError::Overflow)?;
+        self.reward_per_share_paid = reward_per_share;
+        Ok(())
+    }
+
+    /// Credit shares acquired through the marketplace
+    pub fn add_shares(&mut self, amount: u64, reward_per_share: u128) -> Result<()> {
+        self.settle(reward_per_share)?;
+        self.shares = self
+            .shares
+            .checked_add(amount)
+            .ok_or(RoyaltiesError::Overflow)?;
+        Ok(())
+    }
+
+    /// Debit shares sold through the marketplace, keeping payouts already accrued on them
+    pub fn remove_shares(&mut self, amount: u64, reward_per_share: u128) -> Result<()> {
+        self.settle(reward_per_share)?;
+        self.shares = self
+            .shares
+            .checked_sub(amount)
+            .ok_or(RoyaltiesError::NotOwner)?;
+        Ok(())
+    }
+
+    /// Bring the position in line with the `held` shares backed by tokens.
+    /// Shares that left through a plain token transfer are released to the
+    /// pool with the payouts accrued on them since the last settlement; a
+    /// holder with more tokens than credited shares takes up released ones.
+    /// Returns the shares released and taken.
+    pub fn reconcile(&mut self, held: u64, pool: &mut PayoutPool) -> Result<(u64, u64)> {
+        let reward_per_share = pool.reward_per_share;
+        if held < self.shares {
+            let released = self.shares - held;
+            let accrued = accrued(released, self.reward_per_share_paid, reward_per_share)?;
+            self.shares = held;
+            self.settle(reward_per_share)?;
+            pool.release_shares(released, accrued)?;
+            return Ok((released, 0));
+        }
+        let (taken, inherited) = pool.take_released_shares(held - self.shares)?;
+        if taken > 0 {
+            self.add_shares(taken, reward_per_share)?;
+            self.pending = self
+                .pending
+                .checked_add(inherited)
+                .ok_or(RoyaltiesError::Overflow)?;
+        }
+        Ok((0, taken))
+    }
+
+    /// Take every settled payout, leaving nothing pending
+    pub fn withdraw(&mut self) -> Result<u64> {
+        let amount = self.pending;
+        self.pending = 0;
+        self.amount_claimed = self
+            .amount_claimed
+            .checked_add(amount)
+            .ok_or(RoyaltiesError::Overflow)?;
+        Ok(amount)
+    }
+}
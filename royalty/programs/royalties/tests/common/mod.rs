@@ -0,0 +1,432 @@
+//! In-process SVM harness for the royalties program.
+//!
+//! Loads the SBF build of the program (`anchor build` or `cargo build-sbf`) into
+//! `solana-program-test` alongside the bundled SPL Token and Associated Token
+//! programs, with a local mock USDC mint. Metaplex Token Metadata is loaded from
+//! the mainnet binary `scripts/fetch-fixtures.sh` pins in `tests/fixtures`.
+//! Nothing touches the network.
+
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account;
+use anchor_spl::associated_token::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    transfer_fee, ExtensionType, StateWithExtensions,
+};
+use royalties::errors::RoyaltiesError;
+use royalties::instructions::CreateListingArgs;
+use royalties::state::{ProceedsRelease, SaleMode};
+use royalties_client::instructions;
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::signature::{Keypair, Signer};
+#[allow(deprecated)]
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+pub const USDC: u64 = 1_000_000;
+
+/// Skip reason for tests that need the SBF build
+pub const REQUIRES_SBF: &str = "requires target/deploy/royalties.so from `anchor build`";
+/// Where the Metaplex Token Metadata program the royalty NFTs rely on is vendored
+pub const REQUIRES_METAPLEX: &str =
+    "requires programs/royalties/tests/fixtures/mpl_token_metadata.so from scripts/fetch-fixtures.sh";
+
+/// Directory searched for `royalties.so`
+fn sbf_out_dir() -> PathBuf {
+    std::env::var_os("SBF_OUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"))
+}
+
+/// Fixed-price listing terms: `total_shares` at 1 USDC each, 5% of royalties,
+/// 5% creator resale royalty
+pub fn listing_args(
+    payment_mint: Pubkey,
+    total_shares: u64,
+    duration_seconds: u64,
+) -> CreateListingArgs {
+    CreateListingArgs {
+        metadata_uri: "ipfs://royalty".to_string(),
+        percentage_bps: 500,
+        duration_seconds,
+        payment_mint,
+        price: USDC,
+        sale_mode: SaleMode::FixedPrice,
+        presale: None,
+        proceeds_release: ProceedsRelease::Immediate,
+        payout_interval_seconds: 0,
+        min_payout_per_period: 0,
+        total_shares,
+        resale_allowed: true,
+        creator_royalty_bps: 500,
+    }
+}
+
+/// Platform set up by `TestEnv::platform`
+pub struct Platform {
+    pub authority: Keypair,
+    pub treasury: Keypair,
+    /// Treasury's mock USDC account
+    pub treasury_usdc: Pubkey,
+}
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub usdc_mint: Keypair,
+}
+
+impl TestEnv {
+    pub async fn new() -> Self {
+        let out_dir = sbf_out_dir();
+        assert!(
+            out_dir.join("royalties.so").exists(),
+            "{REQUIRES_SBF} (searched {})",
+            out_dir.display()
+        );
+        // solana-program-test falls back to `tests/fixtures` for programs missing from SBF_OUT_DIR
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        assert!(
+            fixtures.join("mpl_token_metadata.so").exists(),
+            "{REQUIRES_METAPLEX} (searched {})",
+            fixtures.display()
+        );
+        std::env::set_var("SBF_OUT_DIR", &out_dir);
+
+        let mut program_test = ProgramTest::new("royalties", royalties::ID, None);
+        program_test.prefer_bpf(true);
+        program_test.add_program("mpl_token_metadata", mpl_token_metadata::ID, None);
+        let context = program_test.start_with_context().await;
+
+        let mut env = Self {
+            context,
+            usdc_mint: Keypair::new(),
+        };
+        let usdc_mint = env.usdc_mint.insecure_clone();
+        env.create_mint(&usdc_mint, 6).await;
+        env
+    }
+
+    pub fn payer(&self) -> Keypair {
+        self.context.payer.insecure_clone()
+    }
+
+    pub fn usdc(&self) -> Pubkey {
+        self.usdc_mint.pubkey()
+    }
+
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let payer = self.payer();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+        // A fresh blockhash keeps retried transactions from being deduplicated
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// Initialize the platform at a 5% primary fee with `payment_mints` allowlisted
+    pub async fn platform(&mut self, payment_mints: &[Pubkey]) -> Platform {
+        let authority = self.wallet().await;
+        let treasury = self.wallet().await;
+        let treasury_usdc = self.fund_usdc(&treasury.pubkey(), 0).await;
+        let mut setup = vec![instructions::initialize(
+            authority.pubkey(),
+            treasury.pubkey(),
+            500,
+        )];
+        setup.extend(
+            payment_mints
+                .iter()
+                .map(|mint| instructions::add_payment_mint(authority.pubkey(), *mint)),
+        );
+        self.send(&setup, &[&authority]).await.unwrap();
+        Platform {
+            authority,
+            treasury,
+            treasury_usdc,
+        }
+    }
+
+    /// Create a listing outside any collection, returning its NFT mint
+    pub async fn create_listing(
+        &mut self,
+        creator: &Keypair,
+        args: CreateListingArgs,
+    ) -> Result<Keypair, BanksClientError> {
+        let nft_mint = Keypair::new();
+        self.send(
+            &[instructions::create_listing(
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                None,
+                args,
+            )],
+            &[creator, &nft_mint],
+        )
+        .await?;
+        Ok(nft_mint)
+    }
+
+    /// Funded wallet
+    pub async fn wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        let payer = self.payer();
+        self.send(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &wallet.pubkey(),
+                10_000_000_000,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        wallet
+    }
+
+    pub async fn create_mint(&mut self, mint: &Keypair, decimals: u8) {
+        let payer = self.payer();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_mint2(
+                    &spl_token::ID,
+                    &mint.pubkey(),
+                    &payer.pubkey(),
+                    None,
+                    decimals,
+                )
+                .unwrap(),
+            ],
+            &[mint],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Token-2022 mint whose transfers withhold `fee_bps` of every amount
+    pub async fn create_fee_mint(&mut self, mint: &Keypair, decimals: u8, fee_bps: u16) {
+        let payer = self.payer();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferFeeConfig,
+        ])
+        .unwrap();
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(space),
+                    space as u64,
+                    &spl_token_2022::ID,
+                ),
+                transfer_fee::instruction::initialize_transfer_fee_config(
+                    &spl_token_2022::ID,
+                    &mint.pubkey(),
+                    None,
+                    None,
+                    fee_bps,
+                    u64::MAX,
+                )
+                .unwrap(),
+                spl_token_2022::instruction::initialize_mint2(
+                    &spl_token_2022::ID,
+                    &mint.pubkey(),
+                    &payer.pubkey(),
+                    None,
+                    decimals,
+                )
+                .unwrap(),
+            ],
+            &[mint],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Token program owning `mint`
+    pub async fn token_program_of(&mut self, mint: &Pubkey) -> Pubkey {
+        self.context
+            .banks_client
+            .get_account(*mint)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("mint {mint} missing"))
+            .owner
+    }
+
+    /// Associated token account of `owner` for `mint` under the mint's token
+    /// program, created on first use
+    pub async fn token_account(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let token_program = self.token_program_of(mint).await;
+        let address = get_associated_token_address_with_program_id(owner, mint, &token_program);
+        if self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_none()
+        {
+            let payer = self.payer();
+            self.send(
+                &[create_associated_token_account(
+                    &payer.pubkey(),
+                    owner,
+                    mint,
+                    &token_program,
+                )],
+                &[],
+            )
+            .await
+            .unwrap();
+        }
+        address
+    }
+
+    /// Funded wallet holding `amount` of the mock USDC
+    pub async fn usdc_wallet(&mut self, amount: u64) -> Keypair {
+        let wallet = self.wallet().await;
+        self.fund_usdc(&wallet.pubkey(), amount).await;
+        wallet
+    }
+
+    /// Creates the owner's USDC account and mints `amount` into it
+    pub async fn fund_usdc(&mut self, owner: &Pubkey, amount: u64) -> Pubkey {
+        let usdc = self.usdc();
+        self.fund(owner, &usdc, amount).await
+    }
+
+    /// Creates the owner's account for a payer-controlled `mint` and mints `amount` into it
+    pub async fn fund(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+        let account = self.token_account(owner, mint).await;
+        if amount > 0 {
+            let payer = self.payer();
+            let token_program = self.token_program_of(mint).await;
+            self.send(
+                &[spl_token_2022::instruction::mint_to(
+                    &token_program,
+                    mint,
+                    &account,
+                    &payer.pubkey(),
+                    &[],
+                    amount,
+                )
+                .unwrap()],
+                &[],
+            )
+            .await
+            .unwrap();
+        }
+        account
+    }
+
+    pub async fn token_balance(&mut self, address: &Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("token account {address} missing"));
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    pub async fn usdc_balance(&mut self, owner: &Pubkey) -> u64 {
+        let address = get_associated_token_address(owner, &self.usdc());
+        self.token_balance(&address).await
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn account_exists(&mut self, address: &Pubkey) -> bool {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    pub async fn fetch<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {address} missing"));
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    /// Current on-chain unix timestamp
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    /// Advance the clock, moving to a new slot so retried transactions get a fresh blockhash
+    pub async fn warp_seconds(&mut self, seconds: i64) {
+        let slot = self.context.banks_client.get_root_slot().await.unwrap();
+        self.context.warp_to_slot(slot + 2).unwrap();
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+}
+
+/// Assert that a transaction failed with the given program error
+pub fn assert_error<T: std::fmt::Debug>(
+    result: Result<T, BanksClientError>,
+    expected: RoyaltiesError,
+) {
+    let expected = u32::from(expected);
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, expected, "unexpected program error"),
+        other => panic!("expected program error {expected}, got {other:?}"),
+    }
+}
@@ -0,0 +1,3124 @@
+//! End-to-end marketplace flows on the SVM.
+//!
+//! Every test is `#[ignore]`d because it needs the artifacts named by
+//! `common::REQUIRES_SBF` and `common::REQUIRES_METAPLEX`; `TestEnv::new` panics
+//! with those messages when one is missing. Run them with `--include-ignored`.
+
+mod common;
+
+use anchor_spl::metadata::mpl_token_metadata::types::{
+    Collection, CollectionDetails, TokenStandard,
+};
+use anchor_spl::metadata::{MasterEditionAccount, MetadataAccount};
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::metadata_pointer::MetadataPointer;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint;
+use common::{assert_error, listing_args, Platform, TestEnv, USDC};
+use royalties::errors::RoyaltiesError;
+use royalties::instructions::{
+    CreateCollectionArgs, CreateListingArgs, UpdateConfigArgs, UpdateListingArgs,
+};
+use royalties::state::{
+    Auction, ListingStatus, Offer, PayoutClaim, PayoutPool, PlatformConfig, Presale,
+    ProceedsEscrow, ProceedsRelease, ResaleListing, RoyaltyListing, SaleMode,
+};
+use royalties_client::instructions::PaymentMint;
+use royalties_client::{instructions, pda, presale};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+#[ignore]
+async fn full_marketplace_lifecycle() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+
+    // initialize: 5% primary fee, 2.5% secondary default
+    let Platform {
+        authority,
+        treasury,
+        treasury_usdc,
+    } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(5 * USDC).await;
+    let alice = env.usdc_wallet(10 * USDC).await;
+    let bob = env.usdc_wallet(10 * USDC).await;
+    let config: PlatformConfig = env.fetch(&pda::platform_config().0).await;
+    assert_eq!(config.authority, authority.pubkey());
+    assert_eq!(config.treasury, treasury.pubkey());
+    assert_eq!(config.platform_fee_bps, 500);
+    assert_eq!(config.secondary_fee_bps, 250);
+    assert_eq!(config.payment_mints, vec![usdc]);
+
+    // create_listing: 4 shares at 1 USDC each
+    let nft_mint = env
+        .create_listing(&creator, listing_args(usdc, 4, 0))
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.status == ListingStatus::Active);
+    assert_eq!(listing.total_shares, 4);
+
+    // Royalty NFT is a Token-2022 mint pointing at its Metaplex metadata
+    let mint_account = env
+        .context
+        .banks_client
+        .get_account(nft_mint.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(mint_account.owner, spl_token_2022::ID);
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+    let pointer = mint_state.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(pointer.authority),
+        Some(royalty_listing)
+    );
+    let metadata_address = pda::metadata(&nft_mint.pubkey()).0;
+    assert_eq!(
+        Option::<Pubkey>::from(pointer.metadata_address),
+        Some(metadata_address)
+    );
+    let metadata: MetadataAccount = env.fetch(&metadata_address).await;
+    assert_eq!(metadata.mint, nft_mint.pubkey());
+    assert_eq!(metadata.update_authority, royalty_listing);
+    assert_eq!(metadata.name.trim_end_matches('\0'), listing.nft_name());
+    assert!(metadata.name.starts_with("Royalty 5% – "));
+    assert_eq!(metadata.symbol.trim_end_matches('\0'), "ROYALTY");
+    assert_eq!(metadata.uri.trim_end_matches('\0'), "ipfs://royalty");
+    assert_eq!(metadata.seller_fee_basis_points, 500);
+    let creators = metadata.creators.clone().unwrap();
+    assert_eq!(creators.len(), 2);
+    assert_eq!(
+        (creators[0].address, creators[0].verified),
+        (royalty_listing, true)
+    );
+    assert_eq!(
+        (creators[1].address, creators[1].verified, creators[1].share),
+        (creator.pubkey(), false, 100)
+    );
+    // Fractional shares never get a master edition
+    assert!(env
+        .context
+        .banks_client
+        .get_account(pda::master_edition(&nft_mint.pubkey()).0)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Purchases fail unless the price matches what the buyer saw and the total fits their cap
+    for (expected_price, max_total_cost) in [(USDC / 2, 3 * USDC), (USDC, 3 * USDC - 1)] {
+        assert_error(
+            env.send(
+                &[instructions::buy_listing(
+                    alice.pubkey(),
+                    creator.pubkey(),
+                    treasury.pubkey(),
+                    nft_mint.pubkey(),
+                    PaymentMint::token(usdc),
+                    3,
+                    expected_price,
+                    max_total_cost,
+                    vec![],
+                    false,
+                )],
+                &[&alice],
+            )
+            .await,
+            RoyaltiesError::PriceMismatch,
+        );
+    }
+
+    // buy_listing: alice buys 3 shares, bob buys the last one
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            3,
+            USDC,
+            3 * USDC,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, 7 * USDC);
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        5 * USDC + 2_850_000
+    );
+    assert_eq!(env.token_balance(&treasury_usdc).await, 150_000);
+
+    env.send(
+        &[instructions::buy_listing(
+            bob.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            1,
+            USDC,
+            USDC,
+            vec![],
+            false,
+        )],
+        &[&bob],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.usdc_balance(&bob.pubkey()).await, 9 * USDC);
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        5 * USDC + 3_800_000
+    );
+    assert_eq!(env.token_balance(&treasury_usdc).await, 200_000);
+
+    let alice_nft = instructions::nft_account(&alice.pubkey(), &nft_mint.pubkey());
+    let bob_nft = instructions::nft_account(&bob.pubkey(), &nft_mint.pubkey());
+    assert_eq!(env.token_balance(&alice_nft).await, 3);
+    assert_eq!(env.token_balance(&bob_nft).await, 1);
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.status == ListingStatus::Sold);
+    assert_eq!(listing.shares_sold, 4);
+
+    // Sold out: further primary purchases are rejected
+    assert_error(
+        env.send(
+            &[instructions::buy_listing(
+                bob.pubkey(),
+                creator.pubkey(),
+                treasury.pubkey(),
+                nft_mint.pubkey(),
+                PaymentMint::token(usdc),
+                1,
+                USDC,
+                USDC,
+                vec![],
+                false,
+            )],
+            &[&bob],
+        )
+        .await,
+        RoyaltiesError::ListingNotActive,
+    );
+
+    // list_for_resale: alice escrows 2 shares for 2 USDC
+    let escrow = Keypair::new();
+    env.send(
+        &[instructions::list_for_resale(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            2 * USDC,
+            2,
+            None,
+        )],
+        &[&alice, &escrow],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&alice_nft).await, 1);
+    assert_eq!(env.token_balance(&escrow.pubkey()).await, 2);
+
+    for (expected_price, max_total_cost) in [(USDC, 2 * USDC), (2 * USDC, USDC)] {
+        assert_error(
+            env.send(
+                &[instructions::buy_resale(
+                    bob.pubkey(),
+                    alice.pubkey(),
+                    creator.pubkey(),
+                    treasury.pubkey(),
+                    nft_mint.pubkey(),
+                    escrow.pubkey(),
+                    PaymentMint::token(usdc),
+                    expected_price,
+                    max_total_cost,
+                )],
+                &[&bob],
+            )
+            .await,
+            RoyaltiesError::PriceMismatch,
+        );
+    }
+
+    // buy_resale: 2.5% platform fee, 5% creator royalty, rest to alice
+    env.send(
+        &[instructions::buy_resale(
+            bob.pubkey(),
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            PaymentMint::token(usdc),
+            2 * USDC,
+            2 * USDC,
+        )],
+        &[&bob],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.usdc_balance(&bob.pubkey()).await, 7 * USDC);
+    assert_eq!(
+        env.usdc_balance(&alice.pubkey()).await,
+        7 * USDC + 1_850_000
+    );
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        5 * USDC + 3_900_000
+    );
+    assert_eq!(env.token_balance(&treasury_usdc).await, 250_000);
+    assert_eq!(env.token_balance(&escrow.pubkey()).await, 0);
+    assert_eq!(env.token_balance(&bob_nft).await, 3);
+    let resale_listing = pda::resale_listing(&royalty_listing, &alice.pubkey()).0;
+    assert!(!env.account_exists(&resale_listing).await);
+
+    let alice_claim: PayoutClaim = env
+        .fetch(&pda::payout_claim(&payout_pool, &alice.pubkey()).0)
+        .await;
+    let bob_claim: PayoutClaim = env
+        .fetch(&pda::payout_claim(&payout_pool, &bob.pubkey()).0)
+        .await;
+    assert_eq!(alice_claim.shares, 1);
+    assert_eq!(bob_claim.shares, 3);
+
+    // cancel_resale: bob lists a share, then takes it back
+    let escrow = Keypair::new();
+    env.send(
+        &[instructions::list_for_resale(
+            bob.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            5 * USDC,
+            1,
+            None,
+        )],
+        &[&bob, &escrow],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&bob_nft).await, 2);
+
+    // update_resale_price: only the seller reprices, and never to zero
+    let resale_listing = pda::resale_listing(&royalty_listing, &bob.pubkey()).0;
+    assert_error(
+        env.send(
+            &[instructions::update_resale_price(
+                bob.pubkey(),
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                0,
+            )],
+            &[&bob],
+        )
+        .await,
+        RoyaltiesError::InvalidPrice,
+    );
+    let mut not_seller = instructions::update_resale_price(
+        alice.pubkey(),
+        creator.pubkey(),
+        nft_mint.pubkey(),
+        USDC,
+    );
+    not_seller.accounts[2].pubkey = resale_listing;
+    assert!(env.send(&[not_seller], &[&alice]).await.is_err());
+    env.send(
+        &[instructions::update_resale_price(
+            bob.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            4 * USDC,
+        )],
+        &[&bob],
+    )
+    .await
+    .unwrap();
+    let resale: ResaleListing = env.fetch(&resale_listing).await;
+    assert_eq!(resale.price, 4 * USDC);
+    assert!(resale.updated_at >= resale.listed_at);
+
+    env.send(
+        &[instructions::cancel_resale(
+            bob.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+        )],
+        &[&bob],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&bob_nft).await, 3);
+    assert_eq!(env.token_balance(&escrow.pubkey()).await, 0);
+    assert!(!env.account_exists(&resale_listing).await);
+
+    // deposit_payout: creator funds 1 USDC for holders
+    let pool_vault = Keypair::new();
+    env.send(
+        &[instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+            USDC,
+            false,
+        )],
+        &[&creator, &pool_vault],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        4 * USDC + 3_900_000
+    );
+    assert_eq!(env.token_balance(&pool_vault.pubkey()).await, USDC);
+
+    // claim_payout: 1 share and 3 shares of 4
+    env.send(
+        &[instructions::claim_payout(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    env.send(
+        &[instructions::claim_payout(
+            bob.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+        )],
+        &[&bob],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, 9_100_000);
+    assert_eq!(env.usdc_balance(&bob.pubkey()).await, 7_750_000);
+    assert_eq!(env.token_balance(&pool_vault.pubkey()).await, 0);
+
+    // Pool is drained: nothing left for alice until the next deposit
+    assert_error(
+        env.send(
+            &[instructions::claim_payout(
+                alice.pubkey(),
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                pool_vault.pubkey(),
+                PaymentMint::token(usdc),
+            )],
+            &[&alice],
+        )
+        .await,
+        RoyaltiesError::PayoutPoolEmpty,
+    );
+
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.total_deposited, USDC);
+    assert_eq!(pool.total_claimed, USDC);
+    let config: PlatformConfig = env.fetch(&pda::platform_config().0).await;
+    assert_eq!(config.total_fees_collected, 250_000);
+}
+
+#[tokio::test]
+#[ignore]
+async fn expired_listing_rejects_deposits_but_keeps_claims() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(5 * USDC).await;
+    let holder = env.usdc_wallet(5 * USDC).await;
+
+    let nft_mint = env
+        .create_listing(&creator, listing_args(usdc, 1, 1_000))
+        .await
+        .unwrap();
+    env.send(
+        &[instructions::buy_listing(
+            holder.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            1,
+            USDC,
+            USDC,
+            vec![],
+            false,
+        )],
+        &[&holder],
+    )
+    .await
+    .unwrap();
+
+    // Selling the only share locks the 1-of-1 NFT behind a master edition
+    let master_edition = pda::master_edition(&nft_mint.pubkey()).0;
+    let edition: MasterEditionAccount = env.fetch(&master_edition).await;
+    assert_eq!(edition.max_supply, Some(0));
+    let metadata: MetadataAccount = env.fetch(&pda::metadata(&nft_mint.pubkey()).0).await;
+    assert_eq!(metadata.token_standard, Some(TokenStandard::NonFungible));
+    let mint_account = env
+        .context
+        .banks_client
+        .get_account(nft_mint.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+    assert_eq!(mint_state.base.supply, 1);
+    assert_eq!(
+        Option::<Pubkey>::from(mint_state.base.mint_authority),
+        Some(master_edition)
+    );
+
+    let pool_vault = Keypair::new();
+    env.send(
+        &[instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+            USDC,
+            false,
+        )],
+        &[&creator, &pool_vault],
+    )
+    .await
+    .unwrap();
+
+    // Not expired yet
+    assert_error(
+        env.send(
+            &[instructions::expire_listing(
+                creator.pubkey(),
+                nft_mint.pubkey(),
+            )],
+            &[],
+        )
+        .await,
+        RoyaltiesError::ListingNotExpired,
+    );
+
+    env.warp_seconds(1_000).await;
+    env.send(
+        &[instructions::expire_listing(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.status == ListingStatus::Expired);
+
+    assert_error(
+        env.send(
+            &[instructions::deposit_payout(
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                pool_vault.pubkey(),
+                PaymentMint::token(usdc),
+                USDC,
+                false,
+            )],
+            &[&creator, &pool_vault],
+        )
+        .await,
+        RoyaltiesError::ListingNotActive,
+    );
+
+    env.send(
+        &[instructions::claim_payout(
+            holder.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+        )],
+        &[&holder],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.usdc_balance(&holder.pubkey()).await, 5 * USDC);
+}
+
+#[tokio::test]
+#[ignore]
+async fn payout_rights_follow_shares_transferred_outside_the_marketplace() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(5 * USDC).await;
+    let alice = env.usdc_wallet(4 * USDC).await;
+    let bob = env.usdc_wallet(USDC).await;
+
+    let nft_mint = env
+        .create_listing(&creator, listing_args(usdc, 4, 0))
+        .await
+        .unwrap();
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            4,
+            USDC,
+            4 * USDC,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+
+    // Alice sends half her shares to Bob with a plain Token-2022 transfer
+    let alice_nft = instructions::nft_account(&alice.pubkey(), &nft_mint.pubkey());
+    let bob_nft = env.token_account(&bob.pubkey(), &nft_mint.pubkey()).await;
+    env.send(
+        &[spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::ID,
+            &alice_nft,
+            &nft_mint.pubkey(),
+            &bob_nft,
+            &alice.pubkey(),
+            &[],
+            2,
+            0,
+        )
+        .unwrap()],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+
+    let pool_vault = Keypair::new();
+    let deposit = instructions::deposit_payout(
+        creator.pubkey(),
+        nft_mint.pubkey(),
+        pool_vault.pubkey(),
+        PaymentMint::token(usdc),
+        USDC,
+        false,
+    );
+    env.send(std::slice::from_ref(&deposit), &[&creator, &pool_vault])
+        .await
+        .unwrap();
+
+    // Bob releases Alice's transferred shares, then takes them up himself
+    let sync = |holder: &Keypair| {
+        instructions::sync_payout_claim(
+            bob.pubkey(),
+            holder.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+        )
+    };
+    env.send(&[sync(&alice), sync(&bob)], &[&bob])
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    let alice_claim: PayoutClaim = env
+        .fetch(&pda::payout_claim(&payout_pool, &alice.pubkey()).0)
+        .await;
+    let bob_claim: PayoutClaim = env
+        .fetch(&pda::payout_claim(&payout_pool, &bob.pubkey()).0)
+        .await;
+    assert_eq!(alice_claim.shares, 2);
+    assert_eq!(bob_claim.shares, 2);
+
+    let claim = |holder: &Keypair| {
+        instructions::claim_payout(
+            holder.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+        )
+    };
+    env.send(&[claim(&alice)], &[&alice]).await.unwrap();
+    env.send(&[claim(&bob)], &[&bob]).await.unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, USDC / 2);
+    assert_eq!(env.usdc_balance(&bob.pubkey()).await, USDC + USDC / 2);
+
+    // Claiming settles against the shares held even without a prior sync
+    env.send(
+        &[spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::ID,
+            &alice_nft,
+            &nft_mint.pubkey(),
+            &bob_nft,
+            &alice.pubkey(),
+            &[],
+            1,
+            0,
+        )
+        .unwrap()],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    env.send(&[deposit], &[&creator, &pool_vault])
+        .await
+        .unwrap();
+    env.send(&[claim(&alice)], &[&alice]).await.unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, USDC / 2 + USDC / 4);
+    env.send(&[claim(&bob)], &[&bob]).await.unwrap();
+    assert_eq!(env.usdc_balance(&bob.pubkey()).await, 2 * USDC + USDC / 4);
+    assert_eq!(env.token_balance(&pool_vault.pubkey()).await, 0);
+
+    // Bob can resell every share he now holds
+    let escrow = Keypair::new();
+    env.send(
+        &[instructions::list_for_resale(
+            bob.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            3 * USDC,
+            3,
+            None,
+        )],
+        &[&bob, &escrow],
+    )
+    .await
+    .unwrap();
+    // Escrowed shares still count as held
+    env.send(&[sync(&bob)], &[&bob]).await.unwrap();
+    let bob_claim: PayoutClaim = env
+        .fetch(&pda::payout_claim(&payout_pool, &bob.pubkey()).0)
+        .await;
+    assert_eq!(bob_claim.shares, 3);
+}
+
+#[tokio::test]
+#[ignore]
+async fn creator_closes_a_partly_sold_primary_sale() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(5 * USDC).await;
+    let alice = env.usdc_wallet(5 * USDC).await;
+
+    let nft_mint = env
+        .create_listing(&creator, listing_args(usdc, 4, 0))
+        .await
+        .unwrap();
+    let close = |signer: &Keypair| {
+        let mut ix = instructions::close_primary_sale(creator.pubkey(), nft_mint.pubkey(), false);
+        ix.accounts[0].pubkey = signer.pubkey();
+        ix
+    };
+    assert_error(
+        env.send(&[close(&creator)], &[&creator]).await,
+        RoyaltiesError::NoSharesSold,
+    );
+
+    let buy = instructions::buy_listing(
+        alice.pubkey(),
+        creator.pubkey(),
+        treasury.pubkey(),
+        nft_mint.pubkey(),
+        PaymentMint::token(usdc),
+        1,
+        USDC,
+        USDC,
+        vec![],
+        false,
+    );
+    env.send(std::slice::from_ref(&buy), &[&alice])
+        .await
+        .unwrap();
+
+    // Deposits made while the sale runs go to the shares sold so far
+    let pool_vault = Keypair::new();
+    let deposit = instructions::deposit_payout(
+        creator.pubkey(),
+        nft_mint.pubkey(),
+        pool_vault.pubkey(),
+        PaymentMint::token(usdc),
+        USDC,
+        false,
+    );
+    let claim = instructions::claim_payout(
+        alice.pubkey(),
+        creator.pubkey(),
+        nft_mint.pubkey(),
+        pool_vault.pubkey(),
+        PaymentMint::token(usdc),
+    );
+    env.send(std::slice::from_ref(&deposit), &[&creator, &pool_vault])
+        .await
+        .unwrap();
+    env.send(std::slice::from_ref(&claim), &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, 5 * USDC);
+
+    assert_error(
+        env.send(&[close(&alice)], &[&alice]).await,
+        RoyaltiesError::Unauthorized,
+    );
+    env.send(&[close(&creator)], &[&creator]).await.unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.status == ListingStatus::Sold);
+    assert_eq!(listing.total_shares, 1);
+    assert_eq!(listing.shares_sold, 1);
+    assert_error(
+        env.send(&[buy], &[&alice]).await,
+        RoyaltiesError::ListingNotActive,
+    );
+
+    // The one share sold keeps taking the whole payout and can be resold
+    env.send(&[deposit], &[&creator, &pool_vault])
+        .await
+        .unwrap();
+    env.send(&[claim], &[&alice]).await.unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, 6 * USDC);
+    let escrow = Keypair::new();
+    env.send(
+        &[instructions::list_for_resale(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            2 * USDC,
+            1,
+            None,
+        )],
+        &[&alice, &escrow],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+#[ignore]
+async fn foreign_payment_mint_is_rejected() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let fake_mint = Keypair::new();
+    env.create_mint(&fake_mint, 6).await;
+    let fake = fake_mint.pubkey();
+
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(5 * USDC).await;
+    let alice = env.usdc_wallet(5 * USDC).await;
+    let bob = env.usdc_wallet(5 * USDC).await;
+    for wallet in [&treasury, &creator, &alice, &bob] {
+        env.fund(&wallet.pubkey(), &fake, 5 * USDC).await;
+    }
+
+    // Listings can only be priced in allowlisted mints
+    assert_error(
+        env.create_listing(&creator, listing_args(fake, 2, 0)).await,
+        RoyaltiesError::PaymentMintNotAllowed,
+    );
+    let nft_mint = env
+        .create_listing(&creator, listing_args(usdc, 2, 0))
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert_eq!(listing.payment_mint, usdc);
+
+    // Primary purchase paid in the wrong mint
+    assert_error(
+        env.send(
+            &[instructions::buy_listing(
+                alice.pubkey(),
+                creator.pubkey(),
+                treasury.pubkey(),
+                nft_mint.pubkey(),
+                PaymentMint::token(fake),
+                2,
+                USDC,
+                2 * USDC,
+                vec![],
+                false,
+            )],
+            &[&alice],
+        )
+        .await,
+        RoyaltiesError::InvalidPaymentMint,
+    );
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            2,
+            USDC,
+            2 * USDC,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+
+    // Resale settled in the wrong mint
+    let escrow = Keypair::new();
+    env.send(
+        &[instructions::list_for_resale(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            USDC,
+            1,
+            None,
+        )],
+        &[&alice, &escrow],
+    )
+    .await
+    .unwrap();
+    assert_error(
+        env.send(
+            &[instructions::buy_resale(
+                bob.pubkey(),
+                alice.pubkey(),
+                creator.pubkey(),
+                treasury.pubkey(),
+                nft_mint.pubkey(),
+                escrow.pubkey(),
+                PaymentMint::token(fake),
+                USDC,
+                USDC,
+            )],
+            &[&bob],
+        )
+        .await,
+        RoyaltiesError::InvalidPaymentMint,
+    );
+    assert_eq!(env.token_balance(&escrow.pubkey()).await, 1);
+
+    // Deposits and claims in the wrong mint
+    let pool_vault = Keypair::new();
+    assert_error(
+        env.send(
+            &[instructions::deposit_payout(
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                pool_vault.pubkey(),
+                PaymentMint::token(fake),
+                USDC,
+                false,
+            )],
+            &[&creator, &pool_vault],
+        )
+        .await,
+        RoyaltiesError::InvalidPaymentMint,
+    );
+    env.send(
+        &[instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+            USDC,
+            false,
+        )],
+        &[&creator, &pool_vault],
+    )
+    .await
+    .unwrap();
+    assert_error(
+        env.send(
+            &[instructions::claim_payout(
+                alice.pubkey(),
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                pool_vault.pubkey(),
+                PaymentMint::token(fake),
+            )],
+            &[&alice],
+        )
+        .await,
+        RoyaltiesError::InvalidPaymentMint,
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn payment_mint_allowlist_is_authority_managed() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let usdt_mint = Keypair::new();
+    env.create_mint(&usdt_mint, 6).await;
+    let usdt = usdt_mint.pubkey();
+
+    let Platform {
+        authority,
+        treasury,
+        ..
+    } = env.platform(&[]).await;
+    let creator = env.wallet().await;
+    let buyer = env.wallet().await;
+    for wallet in [&treasury, &creator, &buyer] {
+        env.fund(&wallet.pubkey(), &usdt, 5 * USDC).await;
+    }
+
+    assert_error(
+        env.send(
+            &[instructions::add_payment_mint(creator.pubkey(), usdt)],
+            &[&creator],
+        )
+        .await,
+        RoyaltiesError::Unauthorized,
+    );
+    for mint in [usdc, usdt] {
+        env.send(
+            &[instructions::add_payment_mint(authority.pubkey(), mint)],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    }
+    assert_error(
+        env.send(
+            &[instructions::add_payment_mint(authority.pubkey(), usdt)],
+            &[&authority],
+        )
+        .await,
+        RoyaltiesError::PaymentMintAlreadyAllowed,
+    );
+
+    // A USDT-priced listing settles in USDT
+    let nft_mint = env
+        .create_listing(&creator, listing_args(usdt, 2, 0))
+        .await
+        .unwrap();
+
+    // Delisting the mint blocks new listings but not existing ones
+    env.send(
+        &[instructions::remove_payment_mint(authority.pubkey(), usdt)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let config: PlatformConfig = env.fetch(&pda::platform_config().0).await;
+    assert_eq!(config.payment_mints, vec![usdc]);
+    assert_error(
+        env.send(
+            &[instructions::remove_payment_mint(authority.pubkey(), usdt)],
+            &[&authority],
+        )
+        .await,
+        RoyaltiesError::PaymentMintNotAllowed,
+    );
+
+    let second_mint = Keypair::new();
+    assert_error(
+        env.send(
+            &[instructions::create_listing(
+                creator.pubkey(),
+                second_mint.pubkey(),
+                None,
+                listing_args(usdt, 2, 0),
+            )],
+            &[&creator, &second_mint],
+        )
+        .await,
+        RoyaltiesError::PaymentMintNotAllowed,
+    );
+
+    env.send(
+        &[instructions::buy_listing(
+            buyer.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdt),
+            1,
+            USDC,
+            USDC,
+            vec![],
+            false,
+        )],
+        &[&buyer],
+    )
+    .await
+    .unwrap();
+    let buyer_usdt = env.token_account(&buyer.pubkey(), &usdt).await;
+    let creator_usdt = env.token_account(&creator.pubkey(), &usdt).await;
+    assert_eq!(env.token_balance(&buyer_usdt).await, 4 * USDC);
+    assert_eq!(env.token_balance(&creator_usdt).await, 5 * USDC + 950_000);
+}
+
+#[tokio::test]
+#[ignore]
+async fn authority_updates_fees_within_bounds() {
+    let mut env = TestEnv::new().await;
+    let Platform {
+        authority,
+        treasury,
+        ..
+    } = env.platform(&[]).await;
+    let outsider = env.wallet().await;
+    let new_treasury = Pubkey::new_unique();
+    let update = |signer: Pubkey, platform_fee_bps, secondary_fee_bps| {
+        instructions::update_config(
+            signer,
+            UpdateConfigArgs {
+                platform_fee_bps,
+                secondary_fee_bps,
+                treasury: Some(new_treasury),
+            },
+        )
+    };
+
+    // Only the authority can change the config
+    assert_error(
+        env.send(&[update(outsider.pubkey(), Some(100), None)], &[&outsider])
+            .await,
+        RoyaltiesError::Unauthorized,
+    );
+
+    // Neither fee may exceed 10%
+    assert_error(
+        env.send(
+            &[update(authority.pubkey(), Some(1_001), None)],
+            &[&authority],
+        )
+        .await,
+        RoyaltiesError::FeeTooHigh,
+    );
+    assert_error(
+        env.send(
+            &[update(authority.pubkey(), None, Some(1_001))],
+            &[&authority],
+        )
+        .await,
+        RoyaltiesError::FeeTooHigh,
+    );
+    let config: PlatformConfig = env.fetch(&pda::platform_config().0).await;
+    assert_eq!(config.platform_fee_bps, 500);
+    assert_eq!(config.secondary_fee_bps, 250);
+    assert_eq!(config.treasury, treasury.pubkey());
+
+    // Exactly 10% is allowed
+    env.send(
+        &[update(authority.pubkey(), Some(1_000), Some(1_000))],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let config: PlatformConfig = env.fetch(&pda::platform_config().0).await;
+    assert_eq!(config.platform_fee_bps, 1_000);
+    assert_eq!(config.secondary_fee_bps, 1_000);
+    assert_eq!(config.treasury, new_treasury);
+
+    // Omitted fees keep their current value
+    env.send(
+        &[update(authority.pubkey(), Some(300), None)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let config: PlatformConfig = env.fetch(&pda::platform_config().0).await;
+    assert_eq!(config.platform_fee_bps, 300);
+    assert_eq!(config.secondary_fee_bps, 1_000);
+}
+
+#[tokio::test]
+#[ignore]
+async fn authority_transfer_takes_two_steps() {
+    let mut env = TestEnv::new().await;
+    let Platform { authority, .. } = env.platform(&[]).await;
+    let first = env.wallet().await;
+    let second = env.wallet().await;
+    let platform_config = pda::platform_config().0;
+    let fee_update = |signer: Pubkey| {
+        instructions::update_config(
+            signer,
+            UpdateConfigArgs {
+                platform_fee_bps: Some(300),
+                secondary_fee_bps: None,
+                treasury: None,
+            },
+        )
+    };
+
+    // Only the authority can propose a successor
+    assert_error(
+        env.send(
+            &[instructions::propose_authority(
+                first.pubkey(),
+                first.pubkey(),
+            )],
+            &[&first],
+        )
+        .await,
+        RoyaltiesError::Unauthorized,
+    );
+
+    // Proposing again replaces the pending candidate
+    for candidate in [&first, &second] {
+        env.send(
+            &[instructions::propose_authority(
+                authority.pubkey(),
+                candidate.pubkey(),
+            )],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    }
+    let config: PlatformConfig = env.fetch(&platform_config).await;
+    assert_eq!(config.authority, authority.pubkey());
+    assert_eq!(config.pending_authority, Some(second.pubkey()));
+
+    // Only the pending candidate can accept
+    for signer in [&first, &authority] {
+        assert_error(
+            env.send(
+                &[instructions::accept_authority(signer.pubkey())],
+                &[signer],
+            )
+            .await,
+            RoyaltiesError::Unauthorized,
+        );
+    }
+    env.send(
+        &[instructions::accept_authority(second.pubkey())],
+        &[&second],
+    )
+    .await
+    .unwrap();
+    let config: PlatformConfig = env.fetch(&platform_config).await;
+    assert_eq!(config.authority, second.pubkey());
+    assert_eq!(config.pending_authority, None);
+    assert_error(
+        env.send(
+            &[instructions::accept_authority(second.pubkey())],
+            &[&second],
+        )
+        .await,
+        RoyaltiesError::Unauthorized,
+    );
+
+    // The previous authority loses access; the new one has it
+    assert_error(
+        env.send(&[fee_update(authority.pubkey())], &[&authority])
+            .await,
+        RoyaltiesError::Unauthorized,
+    );
+    assert_error(
+        env.send(
+            &[instructions::propose_authority(
+                authority.pubkey(),
+                authority.pubkey(),
+            )],
+            &[&authority],
+        )
+        .await,
+        RoyaltiesError::Unauthorized,
+    );
+    env.send(&[fee_update(second.pubkey())], &[&second])
+        .await
+        .unwrap();
+    let config: PlatformConfig = env.fetch(&platform_config).await;
+    assert_eq!(config.platform_fee_bps, 300);
+}
+
+#[tokio::test]
+#[ignore]
+async fn sol_listing_settles_in_lamports() {
+    const SOL: u64 = 1_000_000_000;
+    let mut env = TestEnv::new().await;
+    let sol = native_mint::ID;
+    let Platform { treasury, .. } = env.platform(&[sol]).await;
+    let creator = env.wallet().await;
+    let alice = env.wallet().await;
+    let bob = env.wallet().await;
+
+    // 2 shares at 1 SOL each
+    let nft_mint = env
+        .create_listing(
+            &creator,
+            CreateListingArgs {
+                price: SOL,
+                ..listing_args(sol, 2, 0)
+            },
+        )
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+
+    // buy_listing: 5% platform fee paid in lamports
+    let creator_start = env.lamports(&creator.pubkey()).await;
+    let treasury_start = env.lamports(&treasury.pubkey()).await;
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::sol(),
+            2,
+            SOL,
+            2 * SOL,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.lamports(&creator.pubkey()).await,
+        creator_start + 1_900_000_000
+    );
+    assert_eq!(
+        env.lamports(&treasury.pubkey()).await,
+        treasury_start + 100_000_000
+    );
+
+    // buy_resale: 2.5% platform fee, 5% creator royalty, rest to alice
+    let escrow = Keypair::new();
+    env.send(
+        &[instructions::list_for_resale(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            SOL,
+            1,
+            None,
+        )],
+        &[&alice, &escrow],
+    )
+    .await
+    .unwrap();
+    let resale_listing = pda::resale_listing(&royalty_listing, &alice.pubkey()).0;
+    let resale_rent = env.lamports(&resale_listing).await;
+    let alice_start = env.lamports(&alice.pubkey()).await;
+    env.send(
+        &[instructions::buy_resale(
+            bob.pubkey(),
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            PaymentMint::sol(),
+            SOL,
+            SOL,
+        )],
+        &[&bob],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.lamports(&alice.pubkey()).await,
+        alice_start + resale_rent + 925_000_000
+    );
+    assert_eq!(
+        env.lamports(&creator.pubkey()).await,
+        creator_start + 1_950_000_000
+    );
+    assert_eq!(
+        env.lamports(&treasury.pubkey()).await,
+        treasury_start + 125_000_000
+    );
+
+    // deposit_payout: lamports held by the payout pool itself
+    let pool_rent = env.lamports(&payout_pool).await;
+    let unused_vault = Keypair::new();
+    env.send(
+        &[instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            unused_vault.pubkey(),
+            PaymentMint::sol(),
+            2 * SOL,
+            false,
+        )],
+        &[&creator],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.lamports(&payout_pool).await, pool_rent + 2 * SOL);
+    assert!(!env.account_exists(&unused_vault.pubkey()).await);
+
+    // claim_payout: 1 share each
+    for holder in [&alice, &bob] {
+        let start = env.lamports(&holder.pubkey()).await;
+        env.send(
+            &[instructions::claim_payout(
+                holder.pubkey(),
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                unused_vault.pubkey(),
+                PaymentMint::sol(),
+            )],
+            &[holder],
+        )
+        .await
+        .unwrap();
+        assert_eq!(env.lamports(&holder.pubkey()).await, start + SOL);
+    }
+    assert_eq!(env.lamports(&payout_pool).await, pool_rent);
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.total_claimed, 2 * SOL);
+
+    // accept_offer: the bid sits in the offer itself and settles like buy_resale
+    let offer = pda::offer(&royalty_listing, &alice.pubkey()).0;
+    env.send(
+        &[instructions::make_offer(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            unused_vault.pubkey(),
+            PaymentMint::sol(),
+            1,
+            SOL,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    let offer_rent = env.lamports(&offer).await - SOL;
+    let alice_start = env.lamports(&alice.pubkey()).await;
+    let bob_start = env.lamports(&bob.pubkey()).await;
+    env.send(
+        &[instructions::accept_offer(
+            bob.pubkey(),
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            unused_vault.pubkey(),
+            PaymentMint::sol(),
+            SOL,
+            925_000_000,
+        )],
+        &[&bob],
+    )
+    .await
+    .unwrap();
+    assert!(!env.account_exists(&offer).await);
+    assert_eq!(
+        env.lamports(&alice.pubkey()).await,
+        alice_start + offer_rent
+    );
+    assert_eq!(env.lamports(&bob.pubkey()).await, bob_start + 925_000_000);
+    assert_eq!(
+        env.lamports(&creator.pubkey()).await,
+        creator_start + 2_000_000_000 - 2 * SOL
+    );
+    assert_eq!(
+        env.lamports(&treasury.pubkey()).await,
+        treasury_start + 150_000_000
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn transfer_fee_mint_reconciles_received_amounts() {
+    let mut env = TestEnv::new().await;
+    // Token-2022 stablecoin withholding 1% of every transfer
+    let fee_mint = Keypair::new();
+    env.create_fee_mint(&fee_mint, 6, 100).await;
+    let mint = fee_mint.pubkey();
+    let payment = PaymentMint::token_2022(mint);
+
+    let Platform { treasury, .. } = env.platform(&[mint]).await;
+    let creator = env.wallet().await;
+    let alice = env.wallet().await;
+    let treasury_account = env.fund(&treasury.pubkey(), &mint, 0).await;
+    let creator_account = env.fund(&creator.pubkey(), &mint, 5 * USDC).await;
+    let alice_account = env.fund(&alice.pubkey(), &mint, 5 * USDC).await;
+
+    let nft_mint = env
+        .create_listing(&creator, listing_args(mint, 2, 0))
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+
+    // 2 USDC purchase: recipients bear the 1% fee on what they are sent
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            payment,
+            2,
+            USDC,
+            2 * USDC,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&alice_account).await, 3 * USDC);
+    assert_eq!(
+        env.token_balance(&creator_account).await,
+        5 * USDC + 1_881_000
+    );
+    assert_eq!(env.token_balance(&treasury_account).await, 99_000);
+    let config: PlatformConfig = env.fetch(&pda::platform_config().0).await;
+    assert_eq!(config.total_fees_collected, 99_000);
+
+    // Only what reaches the vault becomes distributable
+    let pool_vault = Keypair::new();
+    env.send(
+        &[instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            payment,
+            USDC,
+            false,
+        )],
+        &[&creator, &pool_vault],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&pool_vault.pubkey()).await, 990_000);
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.total_deposited, 990_000);
+
+    // The full pool is claimable; the holder bears the fee on the way out
+    env.send(
+        &[instructions::claim_payout(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            payment,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&pool_vault.pubkey()).await, 0);
+    assert_eq!(env.token_balance(&alice_account).await, 3 * USDC + 980_100);
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.total_claimed, 990_000);
+}
+
+#[tokio::test]
+#[ignore]
+async fn listings_are_verified_into_the_platform_collection() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { authority, .. } = env.platform(&[usdc]).await;
+    let creator = env.wallet().await;
+
+    // create_collection: sized collection NFT held by the authority
+    let collection_mint = Keypair::new();
+    let collection_args = || CreateCollectionArgs {
+        name: "Royalties.fun".to_string(),
+        symbol: "ROYALTY".to_string(),
+        uri: "ipfs://collection".to_string(),
+    };
+    env.send(
+        &[instructions::create_collection(
+            authority.pubkey(),
+            collection_mint.pubkey(),
+            collection_args(),
+        )],
+        &[&authority, &collection_mint],
+    )
+    .await
+    .unwrap();
+    let platform_config = pda::platform_config().0;
+    let config: PlatformConfig = env.fetch(&platform_config).await;
+    assert_eq!(config.collection_mint, Some(collection_mint.pubkey()));
+    assert_eq!(
+        env.token_balance(&instructions::nft_account(
+            &authority.pubkey(),
+            &collection_mint.pubkey()
+        ))
+        .await,
+        1
+    );
+    let collection_metadata = pda::metadata(&collection_mint.pubkey()).0;
+    let collection: MetadataAccount = env.fetch(&collection_metadata).await;
+    assert_eq!(collection.update_authority, platform_config);
+    assert_eq!(
+        collection.collection_details,
+        Some(CollectionDetails::V1 { size: 0 })
+    );
+    let _: MasterEditionAccount = env
+        .fetch(&pda::master_edition(&collection_mint.pubkey()).0)
+        .await;
+
+    // There is only ever one platform collection
+    let second_collection = Keypair::new();
+    assert_error(
+        env.send(
+            &[instructions::create_collection(
+                authority.pubkey(),
+                second_collection.pubkey(),
+                collection_args(),
+            )],
+            &[&authority, &second_collection],
+        )
+        .await,
+        RoyaltiesError::CollectionAlreadySet,
+    );
+
+    // Listings must join the platform collection once it exists
+    for collection in [None, Some(usdc)] {
+        let nft_mint = Keypair::new();
+        assert_error(
+            env.send(
+                &[instructions::create_listing(
+                    creator.pubkey(),
+                    nft_mint.pubkey(),
+                    collection,
+                    listing_args(usdc, 2, 0),
+                )],
+                &[&creator, &nft_mint],
+            )
+            .await,
+            RoyaltiesError::InvalidCollection,
+        );
+    }
+
+    let nft_mint = Keypair::new();
+    env.send(
+        &[instructions::create_listing(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            Some(collection_mint.pubkey()),
+            listing_args(usdc, 2, 0),
+        )],
+        &[&creator, &nft_mint],
+    )
+    .await
+    .unwrap();
+    let metadata: MetadataAccount = env.fetch(&pda::metadata(&nft_mint.pubkey()).0).await;
+    assert_eq!(
+        metadata.collection,
+        Some(Collection {
+            verified: true,
+            key: collection_mint.pubkey(),
+        })
+    );
+    let collection: MetadataAccount = env.fetch(&collection_metadata).await;
+    assert_eq!(
+        collection.collection_details,
+        Some(CollectionDetails::V1 { size: 1 })
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn creator_updates_active_listing_terms() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(0).await;
+    let alice = env.usdc_wallet(10 * USDC).await;
+
+    let nft_mint = env
+        .create_listing(&creator, listing_args(usdc, 2, 0))
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let metadata_address = pda::metadata(&nft_mint.pubkey()).0;
+
+    let update = |args: UpdateListingArgs| {
+        instructions::update_listing(creator.pubkey(), nft_mint.pubkey(), args, false)
+    };
+    let no_changes = || UpdateListingArgs {
+        price: None,
+        resale_allowed: None,
+        creator_royalty_bps: None,
+        metadata_uri: None,
+    };
+
+    // Updates re-run the create_listing validations
+    for (args, error) in [
+        (
+            UpdateListingArgs {
+                price: Some(0),
+                ..no_changes()
+            },
+            RoyaltiesError::InvalidPrice,
+        ),
+        (
+            UpdateListingArgs {
+                creator_royalty_bps: Some(1_001),
+                ..no_changes()
+            },
+            RoyaltiesError::FeeTooHigh,
+        ),
+        (
+            UpdateListingArgs {
+                metadata_uri: Some(String::new()),
+                ..no_changes()
+            },
+            RoyaltiesError::InvalidMetadataUri,
+        ),
+    ] {
+        assert_error(env.send(&[update(args)], &[&creator]).await, error);
+    }
+
+    // Only the creator can update
+    assert!(env
+        .send(
+            &[instructions::update_listing(
+                alice.pubkey(),
+                nft_mint.pubkey(),
+                UpdateListingArgs {
+                    price: Some(1),
+                    ..no_changes()
+                },
+                false,
+            )],
+            &[&alice],
+        )
+        .await
+        .is_err());
+
+    // Unsold: every term can change, and the Metaplex metadata follows
+    env.send(
+        &[update(UpdateListingArgs {
+            price: Some(2 * USDC),
+            resale_allowed: Some(false),
+            creator_royalty_bps: Some(750),
+            metadata_uri: Some("ipfs://repriced".to_string()),
+        })],
+        &[&creator],
+    )
+    .await
+    .unwrap();
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert_eq!(listing.price, 2 * USDC);
+    assert!(!listing.resale_allowed);
+    assert_eq!(listing.creator_royalty_bps, 750);
+    assert_eq!(listing.metadata_uri, "ipfs://repriced");
+    let metadata: MetadataAccount = env.fetch(&metadata_address).await;
+    assert_eq!(metadata.uri.trim_end_matches('\0'), "ipfs://repriced");
+    assert_eq!(metadata.seller_fee_basis_points, 750);
+    assert_eq!(metadata.creators.clone().unwrap().len(), 2);
+
+    // Buyers pay the new price
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            1,
+            2 * USDC,
+            2 * USDC,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, 8 * USDC);
+
+    // After the first sale the resale terms holders bought under are fixed
+    for args in [
+        UpdateListingArgs {
+            resale_allowed: Some(true),
+            ..no_changes()
+        },
+        UpdateListingArgs {
+            creator_royalty_bps: Some(0),
+            ..no_changes()
+        },
+    ] {
+        assert_error(
+            env.send(&[update(args)], &[&creator]).await,
+            RoyaltiesError::SharesAlreadySold,
+        );
+    }
+    env.send(
+        &[update(UpdateListingArgs {
+            price: Some(USDC),
+            ..no_changes()
+        })],
+        &[&creator],
+    )
+    .await
+    .unwrap();
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert_eq!(listing.price, USDC);
+
+    // Sold out listings are no longer Active
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            1,
+            USDC,
+            USDC,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    assert_error(
+        env.send(
+            &[update(UpdateListingArgs {
+                price: Some(2 * USDC),
+                ..no_changes()
+            })],
+            &[&creator],
+        )
+        .await,
+        RoyaltiesError::ListingNotActive,
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn expired_resale_returns_shares_to_seller() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(0).await;
+    let alice = env.usdc_wallet(10 * USDC).await;
+    let bob = env.usdc_wallet(10 * USDC).await;
+
+    let nft_mint = env
+        .create_listing(&creator, listing_args(usdc, 2, 0))
+        .await
+        .unwrap();
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            2,
+            USDC,
+            2 * USDC,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let resale_listing = pda::resale_listing(&royalty_listing, &alice.pubkey()).0;
+    let alice_nft = instructions::nft_account(&alice.pubkey(), &nft_mint.pubkey());
+
+    // Expiry must lie in the future
+    let now = env.now().await;
+    let escrow = Keypair::new();
+    assert_error(
+        env.send(
+            &[instructions::list_for_resale(
+                alice.pubkey(),
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                escrow.pubkey(),
+                2 * USDC,
+                2,
+                Some(now),
+            )],
+            &[&alice, &escrow],
+        )
+        .await,
+        RoyaltiesError::InvalidResaleExpiry,
+    );
+    env.send(
+        &[instructions::list_for_resale(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            2 * USDC,
+            2,
+            Some(now + 3600),
+        )],
+        &[&alice, &escrow],
+    )
+    .await
+    .unwrap();
+    let resale: ResaleListing = env.fetch(&resale_listing).await;
+    assert_eq!(resale.expires_at, Some(now + 3600));
+
+    // Alice closes her emptied NFT account; the crank recreates it
+    env.send(
+        &[spl_token_2022::instruction::close_account(
+            &spl_token_2022::ID,
+            &alice_nft,
+            &alice.pubkey(),
+            &alice.pubkey(),
+            &[],
+        )
+        .unwrap()],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+
+    // The ask stays in escrow until it expires
+    let close = instructions::close_expired_resale(
+        bob.pubkey(),
+        alice.pubkey(),
+        creator.pubkey(),
+        nft_mint.pubkey(),
+        escrow.pubkey(),
+    );
+    assert_error(
+        env.send(std::slice::from_ref(&close), &[&bob]).await,
+        RoyaltiesError::ResaleNotExpired,
+    );
+
+    env.warp_seconds(3600).await;
+    assert_error(
+        env.send(
+            &[instructions::buy_resale(
+                bob.pubkey(),
+                alice.pubkey(),
+                creator.pubkey(),
+                treasury.pubkey(),
+                nft_mint.pubkey(),
+                escrow.pubkey(),
+                PaymentMint::token(usdc),
+                2 * USDC,
+                2 * USDC,
+            )],
+            &[&bob],
+        )
+        .await,
+        RoyaltiesError::ResaleExpired,
+    );
+    assert_error(
+        env.send(
+            &[instructions::update_resale_price(
+                alice.pubkey(),
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                USDC,
+            )],
+            &[&alice],
+        )
+        .await,
+        RoyaltiesError::ResaleExpired,
+    );
+
+    // close_expired_resale: anyone returns the shares and both rents go to the seller
+    let alice_start = env.lamports(&alice.pubkey()).await;
+    let rent = env.lamports(&resale_listing).await + env.lamports(&escrow.pubkey()).await;
+    env.send(&[close], &[&bob]).await.unwrap();
+    assert_eq!(env.token_balance(&alice_nft).await, 2);
+    assert!(!env.account_exists(&resale_listing).await);
+    assert!(!env.account_exists(&escrow.pubkey()).await);
+    assert_eq!(env.lamports(&alice.pubkey()).await, alice_start + rent);
+}
+
+#[tokio::test]
+#[ignore]
+async fn buyer_offers_escrow_and_settle_with_resale_fees() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform {
+        treasury,
+        treasury_usdc,
+        ..
+    } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(0).await;
+    let alice = env.usdc_wallet(10 * USDC).await;
+    let bob = env.usdc_wallet(10 * USDC).await;
+
+    let nft_mint = env
+        .create_listing(&creator, listing_args(usdc, 2, 0))
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    let offer = pda::offer(&royalty_listing, &bob.pubkey()).0;
+    let alice_nft = instructions::nft_account(&alice.pubkey(), &nft_mint.pubkey());
+    let bob_nft = instructions::nft_account(&bob.pubkey(), &nft_mint.pubkey());
+
+    // Bids are only taken once the primary sale is over
+    let vault = Keypair::new();
+    let make_offer = |vault: &Keypair, amount, price| {
+        instructions::make_offer(
+            bob.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            vault.pubkey(),
+            PaymentMint::token(usdc),
+            amount,
+            price,
+        )
+    };
+    assert_error(
+        env.send(&[make_offer(&vault, 1, USDC)], &[&bob, &vault])
+            .await,
+        RoyaltiesError::ListingNotActive,
+    );
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            2,
+            USDC,
+            2 * USDC,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    assert_error(
+        env.send(&[make_offer(&vault, 3, USDC)], &[&bob, &vault])
+            .await,
+        RoyaltiesError::InvalidShareAmount,
+    );
+
+    // make_offer: bob escrows 4 USDC for both shares
+    env.send(&[make_offer(&vault, 2, 4 * USDC)], &[&bob, &vault])
+        .await
+        .unwrap();
+    assert_eq!(env.usdc_balance(&bob.pubkey()).await, 6 * USDC);
+    assert_eq!(env.token_balance(&vault.pubkey()).await, 4 * USDC);
+    let bid: Offer = env.fetch(&offer).await;
+    assert_eq!(bid.bidder, bob.pubkey());
+    assert_eq!(bid.amount, 2);
+    assert_eq!(bid.price, 4 * USDC);
+
+    // Shares escrowed in a resale listing can't also fill the bid
+    let escrow = Keypair::new();
+    env.send(
+        &[instructions::list_for_resale(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            2 * USDC,
+            1,
+            None,
+        )],
+        &[&alice, &escrow],
+    )
+    .await
+    .unwrap();
+    let accept = |vault: &Keypair, expected_price, min_proceeds| {
+        instructions::accept_offer(
+            alice.pubkey(),
+            bob.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            vault.pubkey(),
+            PaymentMint::token(usdc),
+            expected_price,
+            min_proceeds,
+        )
+    };
+    assert_error(
+        env.send(&[accept(&vault, 4 * USDC, 0)], &[&alice]).await,
+        RoyaltiesError::NotOwner,
+    );
+
+    // cancel_offer: the whole escrow comes back
+    env.send(
+        &[instructions::cancel_offer(
+            bob.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            vault.pubkey(),
+            PaymentMint::token(usdc),
+        )],
+        &[&bob],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.usdc_balance(&bob.pubkey()).await, 10 * USDC);
+    assert!(!env.account_exists(&offer).await);
+    assert!(!env.account_exists(&vault.pubkey()).await);
+
+    // accept_offer: 2.5% platform fee, 5% creator royalty, rest to alice
+    let vault = Keypair::new();
+    env.send(&[make_offer(&vault, 1, 2 * USDC)], &[&bob, &vault])
+        .await
+        .unwrap();
+    let alice_start = env.usdc_balance(&alice.pubkey()).await;
+    let creator_start = env.usdc_balance(&creator.pubkey()).await;
+    let treasury_start = env.token_balance(&treasury_usdc).await;
+    // The seller fills only at the bid and proceeds they agreed to
+    for (expected_price, min_proceeds) in [(4 * USDC, 0), (2 * USDC, 1_850_001)] {
+        assert_error(
+            env.send(&[accept(&vault, expected_price, min_proceeds)], &[&alice])
+                .await,
+            RoyaltiesError::PriceMismatch,
+        );
+    }
+    env.send(&[accept(&vault, 2 * USDC, 1_850_000)], &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(env.usdc_balance(&bob.pubkey()).await, 8 * USDC);
+    assert_eq!(
+        env.usdc_balance(&alice.pubkey()).await,
+        alice_start + 1_850_000
+    );
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        creator_start + 100_000
+    );
+    assert_eq!(
+        env.token_balance(&treasury_usdc).await,
+        treasury_start + 50_000
+    );
+    assert_eq!(env.token_balance(&alice_nft).await, 0);
+    assert_eq!(env.token_balance(&bob_nft).await, 1);
+    assert!(!env.account_exists(&offer).await);
+    assert!(!env.account_exists(&vault.pubkey()).await);
+
+    let alice_claim: PayoutClaim = env
+        .fetch(&pda::payout_claim(&payout_pool, &alice.pubkey()).0)
+        .await;
+    let bob_claim: PayoutClaim = env
+        .fetch(&pda::payout_claim(&payout_pool, &bob.pubkey()).0)
+        .await;
+    assert_eq!(alice_claim.shares, 1);
+    assert_eq!(bob_claim.shares, 1);
+}
+
+#[tokio::test]
+#[ignore]
+async fn english_auction_refunds_outbid_and_settles_to_winner() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform {
+        treasury,
+        treasury_usdc,
+        ..
+    } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(0).await;
+    let alice = env.usdc_wallet(10 * USDC).await;
+    let bob = env.usdc_wallet(10 * USDC).await;
+
+    // Auctions must end in the future
+    let end_time = env.now().await + 3_600;
+    let auction_args = |end_time| CreateListingArgs {
+        sale_mode: SaleMode::EnglishAuction {
+            reserve_price: 2 * USDC,
+            end_time,
+        },
+        ..listing_args(usdc, 2, 0)
+    };
+    assert_error(
+        env.create_listing(&creator, auction_args(end_time - 7_200))
+            .await,
+        RoyaltiesError::InvalidAuction,
+    );
+    let nft_mint = env
+        .create_listing(&creator, auction_args(end_time))
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    let auction = pda::auction(&royalty_listing).0;
+    let auction_vault = PaymentMint::token(usdc).account(&auction).unwrap();
+
+    // Auctioned shares can't be bought at the fixed price
+    assert_error(
+        env.send(
+            &[instructions::buy_listing(
+                alice.pubkey(),
+                creator.pubkey(),
+                treasury.pubkey(),
+                nft_mint.pubkey(),
+                PaymentMint::token(usdc),
+                2,
+                USDC,
+                2 * USDC,
+                vec![],
+                false,
+            )],
+            &[&alice],
+        )
+        .await,
+        RoyaltiesError::SoldByAuction,
+    );
+
+    let bid = |bidder: &Keypair, amount, previous_bidder| {
+        instructions::place_bid(
+            bidder.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            amount,
+            previous_bidder,
+        )
+    };
+
+    // Terms can change until the first bid, and the auction must be passed in
+    let update = |auction| {
+        instructions::update_listing(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            UpdateListingArgs {
+                price: None,
+                resale_allowed: Some(false),
+                creator_royalty_bps: None,
+                metadata_uri: None,
+            },
+            auction,
+        )
+    };
+    assert_error(
+        env.send(&[update(false)], &[&creator]).await,
+        RoyaltiesError::InvalidAuction,
+    );
+    env.send(&[update(true)], &[&creator]).await.unwrap();
+
+    // place_bid: the first bid must meet the reserve
+    assert_error(
+        env.send(&[bid(&alice, USDC, None)], &[&alice]).await,
+        RoyaltiesError::BidTooLow,
+    );
+    env.send(&[bid(&alice, 2 * USDC, None)], &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, 8 * USDC);
+    assert_eq!(env.token_balance(&auction_vault).await, 2 * USDC);
+
+    // Later bids must beat the highest bid, which is refunded
+    assert_error(
+        env.send(&[bid(&bob, 2 * USDC, Some(alice.pubkey()))], &[&bob])
+            .await,
+        RoyaltiesError::BidTooLow,
+    );
+    env.send(&[bid(&bob, 3 * USDC, Some(alice.pubkey()))], &[&bob])
+        .await
+        .unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, 10 * USDC);
+    assert_eq!(env.usdc_balance(&bob.pubkey()).await, 7 * USDC);
+    assert_eq!(env.token_balance(&auction_vault).await, 3 * USDC);
+    let state: Auction = env.fetch(&auction).await;
+    assert_eq!(state.highest_bidder, Some(bob.pubkey()));
+    assert_eq!(state.highest_bid, 3 * USDC);
+    assert_eq!(state.bid_count, 2);
+
+    // Bidders are protected from the creator pulling the lot or changing its terms
+    assert_error(
+        env.send(&[update(true)], &[&creator]).await,
+        RoyaltiesError::AuctionHasBids,
+    );
+    assert_error(
+        env.send(
+            &[instructions::cancel_listing(
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                true,
+                false,
+            )],
+            &[&creator],
+        )
+        .await,
+        RoyaltiesError::AuctionHasBids,
+    );
+
+    let settle = |payer: &Keypair| {
+        instructions::settle_auction(
+            payer.pubkey(),
+            bob.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            false,
+        )
+    };
+    assert_error(
+        env.send(&[settle(&alice)], &[&alice]).await,
+        RoyaltiesError::AuctionNotEnded,
+    );
+
+    env.warp_seconds(3_601).await;
+    assert_error(
+        env.send(&[bid(&alice, 4 * USDC, Some(bob.pubkey()))], &[&alice])
+            .await,
+        RoyaltiesError::AuctionEnded,
+    );
+
+    // settle_auction: anyone can settle; 5% platform fee, rest to the creator
+    env.send(&[settle(&alice)], &[&alice]).await.unwrap();
+    let bob_nft = instructions::nft_account(&bob.pubkey(), &nft_mint.pubkey());
+    assert_eq!(env.token_balance(&bob_nft).await, 2);
+    assert_eq!(env.usdc_balance(&creator.pubkey()).await, 2_850_000);
+    assert_eq!(env.token_balance(&treasury_usdc).await, 150_000);
+    assert!(!env.account_exists(&auction).await);
+    assert!(!env.account_exists(&auction_vault).await);
+
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.status == ListingStatus::Sold);
+    assert_eq!(listing.shares_sold, 2);
+    let bob_claim: PayoutClaim = env
+        .fetch(&pda::payout_claim(&payout_pool, &bob.pubkey()).0)
+        .await;
+    assert_eq!(bob_claim.shares, 2);
+}
+
+#[tokio::test]
+#[ignore]
+async fn expired_auction_without_bids_is_cancelled_for_its_rent() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(0).await;
+
+    // The auction ends an hour in and the royalty term an hour later
+    let end_time = env.now().await + 3_600;
+    let nft_mint = env
+        .create_listing(
+            &creator,
+            CreateListingArgs {
+                sale_mode: SaleMode::EnglishAuction {
+                    reserve_price: 2 * USDC,
+                    end_time,
+                },
+                ..listing_args(usdc, 2, 7_200)
+            },
+        )
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    let auction = pda::auction(&royalty_listing).0;
+
+    // Nobody bids, and anyone cranks the listing to Expired once the term ends
+    env.warp_seconds(7_201).await;
+    env.send(
+        &[instructions::expire_listing(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.status == ListingStatus::Expired);
+
+    // The creator cancels the expired listing and recovers the rent
+    let rent = env.lamports(&royalty_listing).await
+        + env.lamports(&payout_pool).await
+        + env.lamports(&auction).await;
+    let before = env.lamports(&creator.pubkey()).await;
+    env.send(
+        &[instructions::cancel_listing(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            true,
+            false,
+        )],
+        &[&creator],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.lamports(&creator.pubkey()).await, before + rent);
+    for account in [royalty_listing, payout_pool, auction] {
+        assert!(!env.account_exists(&account).await);
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn dutch_auction_price_decays_to_floor() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform {
+        treasury,
+        treasury_usdc,
+        ..
+    } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(0).await;
+    let buyer = env.usdc_wallet(10 * USDC).await;
+
+    // 4 USDC per share falling to 1 USDC over an hour, starting in 100 seconds
+    let start_time = env.now().await + 100;
+    let dutch_args = |start_price, floor_price| CreateListingArgs {
+        sale_mode: SaleMode::DutchAuction {
+            start_price,
+            floor_price,
+            start_time,
+            decay_period: 3_600,
+        },
+        ..listing_args(usdc, 4, 0)
+    };
+    assert_error(
+        env.create_listing(&creator, dutch_args(USDC, 4 * USDC))
+            .await,
+        RoyaltiesError::InvalidPrice,
+    );
+    let mut no_decay = dutch_args(4 * USDC, USDC);
+    if let SaleMode::DutchAuction { decay_period, .. } = &mut no_decay.sale_mode {
+        *decay_period = 0;
+    }
+    assert_error(
+        env.create_listing(&creator, no_decay).await,
+        RoyaltiesError::InvalidDecayPeriod,
+    );
+    let nft_mint = env
+        .create_listing(&creator, dutch_args(4 * USDC, USDC))
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let buyer_nft = instructions::nft_account(&buyer.pubkey(), &nft_mint.pubkey());
+
+    // The decay sets the price; a fixed price can't be slipped in
+    assert_error(
+        env.send(
+            &[instructions::update_listing(
+                creator.pubkey(),
+                nft_mint.pubkey(),
+                UpdateListingArgs {
+                    price: Some(USDC),
+                    resale_allowed: None,
+                    creator_royalty_bps: None,
+                    metadata_uri: None,
+                },
+                false,
+            )],
+            &[&creator],
+        )
+        .await,
+        RoyaltiesError::SoldByAuction,
+    );
+
+    let buy = |amount, max_price, max_total_cost| {
+        instructions::buy_listing(
+            buyer.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            amount,
+            max_price,
+            max_total_cost,
+            vec![],
+            false,
+        )
+    };
+    assert_error(
+        env.send(&[buy(1, 4 * USDC, 4 * USDC)], &[&buyer]).await,
+        RoyaltiesError::AuctionNotStarted,
+    );
+
+    // Half way through the decay the price is 2.5 USDC; the buyer's max price caps it
+    env.warp_seconds(100 + 1_800).await;
+    assert_error(
+        env.send(&[buy(1, 2 * USDC, 3 * USDC)], &[&buyer]).await,
+        RoyaltiesError::PriceMismatch,
+    );
+    assert_error(
+        env.send(&[buy(2, 3 * USDC, 4 * USDC)], &[&buyer]).await,
+        RoyaltiesError::PriceMismatch,
+    );
+    env.send(&[buy(1, 3 * USDC, 3 * USDC)], &[&buyer])
+        .await
+        .unwrap();
+    assert_eq!(env.usdc_balance(&buyer.pubkey()).await, 7_500_000);
+    assert_eq!(env.usdc_balance(&creator.pubkey()).await, 2_375_000);
+    assert_eq!(env.token_balance(&treasury_usdc).await, 125_000);
+
+    // After the decay period the price rests at the floor
+    env.warp_seconds(3_600).await;
+    env.send(&[buy(3, USDC, 3 * USDC)], &[&buyer])
+        .await
+        .unwrap();
+    assert_eq!(env.usdc_balance(&buyer.pubkey()).await, 4_500_000);
+    assert_eq!(env.token_balance(&buyer_nft).await, 4);
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.status == ListingStatus::Sold);
+}
+
+#[tokio::test]
+#[ignore]
+async fn presale_admits_only_allowlisted_buyers_until_it_ends() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(0).await;
+    let fan = env.usdc_wallet(10 * USDC).await;
+    let stranger = env.usdc_wallet(10 * USDC).await;
+
+    let allowlist = [Pubkey::new_unique(), fan.pubkey(), Pubkey::new_unique()];
+    let merkle_root = presale::merkle_root(&allowlist);
+    let now = env.now().await;
+    let presale_args = |ends_at| CreateListingArgs {
+        presale: Some(Presale {
+            merkle_root,
+            ends_at,
+        }),
+        ..listing_args(usdc, 4, 0)
+    };
+    assert_error(
+        env.create_listing(&creator, presale_args(now)).await,
+        RoyaltiesError::InvalidPresale,
+    );
+    let nft_mint = env
+        .create_listing(&creator, presale_args(now + 600))
+        .await
+        .unwrap();
+
+    let buy = |buyer: &Keypair, proof| {
+        instructions::buy_listing(
+            buyer.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            1,
+            USDC,
+            USDC,
+            proof,
+            false,
+        )
+    };
+    let fan_proof = presale::merkle_proof(&allowlist, &fan.pubkey()).unwrap();
+
+    // During the presale only allowlisted wallets with a valid proof can buy
+    assert_error(
+        env.send(&[buy(&stranger, vec![])], &[&stranger]).await,
+        RoyaltiesError::NotOnPresaleList,
+    );
+    assert_error(
+        env.send(&[buy(&stranger, fan_proof.clone())], &[&stranger])
+            .await,
+        RoyaltiesError::NotOnPresaleList,
+    );
+    assert_error(
+        env.send(&[buy(&fan, vec![])], &[&fan]).await,
+        RoyaltiesError::NotOnPresaleList,
+    );
+    env.send(&[buy(&fan, fan_proof)], &[&fan]).await.unwrap();
+    assert_eq!(env.usdc_balance(&fan.pubkey()).await, 9 * USDC);
+
+    // Once it ends the listing is public
+    env.warp_seconds(600).await;
+    env.send(&[buy(&stranger, vec![])], &[&stranger])
+        .await
+        .unwrap();
+    let stranger_nft = instructions::nft_account(&stranger.pubkey(), &nft_mint.pubkey());
+    assert_eq!(env.token_balance(&stranger_nft).await, 1);
+}
+
+#[tokio::test]
+#[ignore]
+async fn escrowed_proceeds_unlock_by_payout_tranches_and_vesting() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(10 * USDC).await;
+    let buyer = env.usdc_wallet(10 * USDC).await;
+
+    // Two tranches, each unlocked by a payout of at least 0.5 USDC every 1000 seconds
+    let nft_mint = env
+        .create_listing(
+            &creator,
+            CreateListingArgs {
+                proceeds_release: ProceedsRelease::PayoutTranches {
+                    count: 2,
+                    interval: 1_000,
+                    min_deposit: USDC / 2,
+                },
+                ..listing_args(usdc, 2, 0)
+            },
+        )
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let proceeds_escrow = pda::proceeds_escrow(&royalty_listing).0;
+    let proceeds_vault = PaymentMint::token(usdc).account(&proceeds_escrow).unwrap();
+
+    // buy_listing: the creator's 95% is escrowed, not paid out
+    env.send(
+        &[instructions::buy_listing(
+            buyer.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            2,
+            USDC,
+            2 * USDC,
+            vec![],
+            true,
+        )],
+        &[&buyer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.usdc_balance(&creator.pubkey()).await, 10 * USDC);
+    assert_eq!(env.token_balance(&proceeds_vault).await, 1_900_000);
+    let escrow: ProceedsEscrow = env.fetch(&proceeds_escrow).await;
+    assert_eq!(escrow.total_escrowed, 1_900_000);
+
+    let release = instructions::release_proceeds(
+        creator.pubkey(),
+        nft_mint.pubkey(),
+        PaymentMint::token(usdc),
+    );
+    assert_error(
+        env.send(std::slice::from_ref(&release), &[&creator]).await,
+        RoyaltiesError::NoProceedsToRelease,
+    );
+
+    let pool_vault = Keypair::new();
+    let deposit = |amount, escrowed_proceeds| {
+        instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+            amount,
+            escrowed_proceeds,
+        )
+    };
+    assert_error(
+        env.send(&[deposit(USDC, false)], &[&creator, &pool_vault])
+            .await,
+        RoyaltiesError::InvalidProceedsRelease,
+    );
+
+    // Dust deposits don't unlock anything
+    env.send(&[deposit(USDC / 10, true)], &[&creator, &pool_vault])
+        .await
+        .unwrap();
+    assert_error(
+        env.send(std::slice::from_ref(&release), &[&creator]).await,
+        RoyaltiesError::NoProceedsToRelease,
+    );
+
+    // An on-time payout unlocks half, once per window
+    env.send(&[deposit(USDC, true)], &[&creator, &pool_vault])
+        .await
+        .unwrap();
+    env.warp_seconds(1).await;
+    env.send(&[deposit(USDC, true)], &[&creator, &pool_vault])
+        .await
+        .unwrap();
+    env.send(std::slice::from_ref(&release), &[&creator])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        7_900_000 + 950_000
+    );
+    assert_error(
+        env.send(std::slice::from_ref(&release), &[&creator]).await,
+        RoyaltiesError::NoProceedsToRelease,
+    );
+
+    // A late payout restarts the schedule without unlocking
+    env.warp_seconds(2_500).await;
+    env.send(&[deposit(USDC, true)], &[&creator, &pool_vault])
+        .await
+        .unwrap();
+    assert_error(
+        env.send(std::slice::from_ref(&release), &[&creator]).await,
+        RoyaltiesError::NoProceedsToRelease,
+    );
+    env.warp_seconds(500).await;
+    env.send(&[deposit(USDC, true)], &[&creator, &pool_vault])
+        .await
+        .unwrap();
+    env.send(std::slice::from_ref(&release), &[&creator])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        5_900_000 + 1_900_000
+    );
+    assert_eq!(env.token_balance(&proceeds_vault).await, 0);
+    let escrow: ProceedsEscrow = env.fetch(&proceeds_escrow).await;
+    assert_eq!(escrow.tranches_unlocked, 2);
+    assert_eq!(escrow.total_released, 1_900_000);
+
+    // Linear vesting releases proceeds pro rata over the period from the first sale
+    let nft_mint = env
+        .create_listing(
+            &creator,
+            CreateListingArgs {
+                proceeds_release: ProceedsRelease::LinearVesting { period: 1_000 },
+                ..listing_args(usdc, 1, 0)
+            },
+        )
+        .await
+        .unwrap();
+    env.send(
+        &[instructions::buy_listing(
+            buyer.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            1,
+            USDC,
+            USDC,
+            vec![],
+            true,
+        )],
+        &[&buyer],
+    )
+    .await
+    .unwrap();
+    let creator_start = env.usdc_balance(&creator.pubkey()).await;
+    let release = instructions::release_proceeds(
+        creator.pubkey(),
+        nft_mint.pubkey(),
+        PaymentMint::token(usdc),
+    );
+    env.warp_seconds(250).await;
+    env.send(std::slice::from_ref(&release), &[&creator])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        creator_start + 237_500
+    );
+    env.warp_seconds(1_000).await;
+    env.send(&[release], &[&creator]).await.unwrap();
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        creator_start + 950_000
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn abandoned_payout_tranches_are_forfeited_to_holders() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(10 * USDC).await;
+    let buyer = env.usdc_wallet(10 * USDC).await;
+
+    let tranches = |count, duration_seconds| CreateListingArgs {
+        proceeds_release: ProceedsRelease::PayoutTranches {
+            count,
+            interval: 1_000,
+            min_deposit: USDC / 2,
+        },
+        ..listing_args(usdc, 2, duration_seconds)
+    };
+    // Every tranche must come due within the listing term
+    assert_error(
+        env.create_listing(&creator, tranches(3, 2_000)).await,
+        RoyaltiesError::InvalidProceedsRelease,
+    );
+
+    // Cancelling an unsold listing closes its proceeds escrow too
+    let unsold = env.create_listing(&creator, tranches(2, 0)).await.unwrap();
+    let cancel = |escrowed_proceeds| {
+        instructions::cancel_listing(creator.pubkey(), unsold.pubkey(), false, escrowed_proceeds)
+    };
+    assert_error(
+        env.send(&[cancel(false)], &[&creator]).await,
+        RoyaltiesError::InvalidProceedsRelease,
+    );
+    env.send(&[cancel(true)], &[&creator]).await.unwrap();
+    let unsold_listing = pda::royalty_listing(&creator.pubkey(), &unsold.pubkey()).0;
+    assert!(
+        !env.account_exists(&pda::proceeds_escrow(&unsold_listing).0)
+            .await
+    );
+
+    let nft_mint = env.create_listing(&creator, tranches(2, 0)).await.unwrap();
+    env.send(
+        &[instructions::buy_listing(
+            buyer.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            2,
+            USDC,
+            2 * USDC,
+            vec![],
+            true,
+        )],
+        &[&buyer],
+    )
+    .await
+    .unwrap();
+
+    let pool_vault = Keypair::new();
+    let forfeit = instructions::forfeit_proceeds(
+        buyer.pubkey(),
+        creator.pubkey(),
+        nft_mint.pubkey(),
+        pool_vault.pubkey(),
+        PaymentMint::token(usdc),
+    );
+    assert_error(
+        env.send(std::slice::from_ref(&forfeit), &[&buyer, &pool_vault])
+            .await,
+        RoyaltiesError::ProceedsNotForfeitable,
+    );
+
+    // One on-time payout unlocks the first tranche, then the creator stops
+    env.warp_seconds(1).await;
+    env.send(
+        &[instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+            USDC,
+            true,
+        )],
+        &[&creator, &pool_vault],
+    )
+    .await
+    .unwrap();
+
+    // A missed tranche can still be caught up on for one more interval
+    env.warp_seconds(2_500).await;
+    assert_error(
+        env.send(std::slice::from_ref(&forfeit), &[&buyer, &pool_vault])
+            .await,
+        RoyaltiesError::ProceedsNotForfeitable,
+    );
+    env.warp_seconds(600).await;
+    env.send(std::slice::from_ref(&forfeit), &[&buyer, &pool_vault])
+        .await
+        .unwrap();
+    assert_error(
+        env.send(&[forfeit], &[&buyer, &pool_vault]).await,
+        RoyaltiesError::ProceedsNotForfeitable,
+    );
+
+    // Holders claim the locked tranche with the payout; the creator keeps the unlocked one
+    env.send(
+        &[instructions::claim_payout(
+            buyer.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+        )],
+        &[&buyer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.usdc_balance(&buyer.pubkey()).await,
+        8 * USDC + USDC + 950_000
+    );
+    let release = instructions::release_proceeds(
+        creator.pubkey(),
+        nft_mint.pubkey(),
+        PaymentMint::token(usdc),
+    );
+    env.send(std::slice::from_ref(&release), &[&creator])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.usdc_balance(&creator.pubkey()).await,
+        9 * USDC + 950_000
+    );
+    assert_error(
+        env.send(&[release], &[&creator]).await,
+        RoyaltiesError::NoProceedsToRelease,
+    );
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let escrow: ProceedsEscrow = env.fetch(&pda::proceeds_escrow(&royalty_listing).0).await;
+    assert_eq!(escrow.forfeited, 950_000);
+    assert_eq!(escrow.total_released, 950_000);
+}
+
+#[tokio::test]
+#[ignore]
+async fn missed_scheduled_payout_marks_listing_delinquent_until_caught_up() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(10 * USDC).await;
+    let alice = env.usdc_wallet(10 * USDC).await;
+    let bob = env.usdc_wallet(10 * USDC).await;
+
+    // The creator commits to a payout every 1000 seconds once sold out
+    let nft_mint = env
+        .create_listing(
+            &creator,
+            CreateListingArgs {
+                payout_interval_seconds: 1_000,
+                min_payout_per_period: USDC / 2,
+                ..listing_args(usdc, 2, 0)
+            },
+        )
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    let mark = instructions::mark_delinquent(creator.pubkey(), nft_mint.pubkey());
+
+    // No payout is due before the first sale
+    assert_error(
+        env.send(std::slice::from_ref(&mark), &[]).await,
+        RoyaltiesError::PayoutNotOverdue,
+    );
+
+    env.send(
+        &[instructions::buy_listing(
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            2,
+            USDC,
+            2 * USDC,
+            vec![],
+            false,
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    let sold_at = env.now().await;
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.payout_interval_seconds, 1_000);
+    assert_eq!(pool.next_payout_due, sold_at + 1_000);
+
+    assert_error(
+        env.send(std::slice::from_ref(&mark), &[]).await,
+        RoyaltiesError::PayoutNotOverdue,
+    );
+
+    // Anyone (here the fee payer) can flag the listing once the deadline passes
+    env.warp_seconds(1_001).await;
+    env.send(std::slice::from_ref(&mark), &[]).await.unwrap();
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.delinquent);
+    assert_error(
+        env.send(std::slice::from_ref(&mark), &[]).await,
+        RoyaltiesError::AlreadyDelinquent,
+    );
+
+    // Resales stay open while the listing is flagged
+    let escrow = Keypair::new();
+    env.send(
+        &[instructions::list_for_resale(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            USDC,
+            1,
+            None,
+        )],
+        &[&alice, &escrow],
+    )
+    .await
+    .unwrap();
+    env.send(
+        &[instructions::buy_resale(
+            bob.pubkey(),
+            alice.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            escrow.pubkey(),
+            PaymentMint::token(usdc),
+            USDC,
+            USDC,
+        )],
+        &[&bob],
+    )
+    .await
+    .unwrap();
+
+    // A dust deposit below the committed minimum settles nothing
+    let pool_vault = Keypair::new();
+    env.send(
+        &[instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+            1,
+            false,
+        )],
+        &[&creator, &pool_vault],
+    )
+    .await
+    .unwrap();
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.delinquent);
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.next_payout_due, sold_at + 1_000);
+
+    // A deposit of at least the minimum settles the missed period and lifts the flag
+    env.send(
+        &[instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+            USDC,
+            false,
+        )],
+        &[&creator, &pool_vault],
+    )
+    .await
+    .unwrap();
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(!listing.delinquent);
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.next_payout_due, sold_at + 2_000);
+    assert_error(
+        env.send(std::slice::from_ref(&mark), &[]).await,
+        RoyaltiesError::PayoutNotOverdue,
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn partly_sold_listing_owes_scheduled_payouts() {
+    let mut env = TestEnv::new().await;
+    let usdc = env.usdc();
+    let Platform { treasury, .. } = env.platform(&[usdc]).await;
+    let creator = env.usdc_wallet(10 * USDC).await;
+    let alice = env.usdc_wallet(10 * USDC).await;
+    let bob = env.usdc_wallet(10 * USDC).await;
+
+    let nft_mint = env
+        .create_listing(
+            &creator,
+            CreateListingArgs {
+                payout_interval_seconds: 1_000,
+                min_payout_per_period: USDC / 2,
+                ..listing_args(usdc, 4, 0)
+            },
+        )
+        .await
+        .unwrap();
+    let royalty_listing = pda::royalty_listing(&creator.pubkey(), &nft_mint.pubkey()).0;
+    let payout_pool = pda::payout_pool(&royalty_listing).0;
+    let mark = instructions::mark_delinquent(creator.pubkey(), nft_mint.pubkey());
+    let buy = |buyer: &Keypair| {
+        instructions::buy_listing(
+            buyer.pubkey(),
+            creator.pubkey(),
+            treasury.pubkey(),
+            nft_mint.pubkey(),
+            PaymentMint::token(usdc),
+            1,
+            USDC,
+            USDC,
+            vec![],
+            false,
+        )
+    };
+
+    // The schedule starts with the first share sold, not at sell-out
+    env.send(&[buy(&alice)], &[&alice]).await.unwrap();
+    let sold_at = env.now().await;
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.next_payout_due, sold_at + 1_000);
+
+    // Later sales leave it running
+    env.warp_seconds(500).await;
+    env.send(&[buy(&bob)], &[&bob]).await.unwrap();
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.next_payout_due, sold_at + 1_000);
+
+    // Leaving the sale open doesn't stop holders flagging a missed payout
+    env.warp_seconds(501).await;
+    env.send(std::slice::from_ref(&mark), &[]).await.unwrap();
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(listing.status == ListingStatus::Active);
+    assert!(listing.delinquent);
+
+    // A deposit is split across the shares sold and lifts the flag
+    let pool_vault = Keypair::new();
+    env.send(
+        &[instructions::deposit_payout(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+            USDC,
+            false,
+        )],
+        &[&creator, &pool_vault],
+    )
+    .await
+    .unwrap();
+    let listing: RoyaltyListing = env.fetch(&royalty_listing).await;
+    assert!(!listing.delinquent);
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.next_payout_due, sold_at + 2_000);
+    env.send(
+        &[instructions::claim_payout(
+            alice.pubkey(),
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            pool_vault.pubkey(),
+            PaymentMint::token(usdc),
+        )],
+        &[&alice],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.usdc_balance(&alice.pubkey()).await, 9 * USDC + USDC / 2);
+
+    // Closing the sale keeps the running schedule
+    env.send(
+        &[instructions::close_primary_sale(
+            creator.pubkey(),
+            nft_mint.pubkey(),
+            false,
+        )],
+        &[&creator],
+    )
+    .await
+    .unwrap();
+    let pool: PayoutPool = env.fetch(&payout_pool).await;
+    assert_eq!(pool.next_payout_due, sold_at + 2_000);
+}
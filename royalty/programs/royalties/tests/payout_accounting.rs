@@ -0,0 +1,247 @@
+use anchor_lang::prelude::Pubkey;
+use royalties::state::{PayoutClaim, PayoutPool};
+
+const TOTAL_SHARES: u64 = 4;
+
+fn pool() -> PayoutPool {
+    PayoutPool {
+        royalty_listing: Pubkey::new_unique(),
+        creator: Pubkey::new_unique(),
+        total_deposited: 0,
+        total_claimed: 0,
+        deposited_at: 0,
+        reward_per_share: 0,
+        payout_interval_seconds: 0,
+        min_payout_per_period: 0,
+        next_payout_due: 0,
+        released_shares: 0,
+        released_reward_per_share_paid: 0,
+        released_pending: 0,
+        bump: 0,
+    }
+}
+
+fn position(pool: &PayoutPool, shares: u64) -> PayoutClaim {
+    let mut claim = PayoutClaim {
+        payout_pool: Pubkey::new_unique(),
+        holder: Pubkey::new_unique(),
+        shares: 0,
+        reward_per_share_paid: 0,
+        pending: 0,
+        amount_claimed: 0,
+        claimed_at: 0,
+        bump: 0,
+    };
+    claim.add_shares(shares, pool.reward_per_share).unwrap();
+    claim
+}
+
+/// Mirrors claim_payout: settle, withdraw, and book the claim on the pool
+fn claim(pool: &mut PayoutPool, claim: &mut PayoutClaim) -> u64 {
+    claim.settle(pool.reward_per_share).unwrap();
+    let amount = claim.withdraw().unwrap();
+    assert!(amount <= pool.available().unwrap());
+    pool.total_claimed += amount;
+    amount
+}
+
+#[test]
+fn two_deposits_before_a_claim_are_both_claimable() {
+    let mut pool = pool();
+    let mut holder = position(&pool, TOTAL_SHARES);
+
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+    pool.record_deposit(500_000, TOTAL_SHARES).unwrap();
+
+    assert_eq!(claim(&mut pool, &mut holder), 1_500_000);
+    assert_eq!(claim(&mut pool, &mut holder), 0);
+    assert_eq!(pool.available().unwrap(), 0);
+}
+
+#[test]
+fn deposits_and_claims_interleave() {
+    let mut pool = pool();
+    let mut holder = position(&pool, TOTAL_SHARES);
+
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+    assert_eq!(claim(&mut pool, &mut holder), 1_000_000);
+
+    pool.record_deposit(2_000_000, TOTAL_SHARES).unwrap();
+    pool.record_deposit(3_000_000, TOTAL_SHARES).unwrap();
+    assert_eq!(claim(&mut pool, &mut holder), 5_000_000);
+
+    pool.record_deposit(4_000_000, TOTAL_SHARES).unwrap();
+    assert_eq!(claim(&mut pool, &mut holder), 4_000_000);
+
+    assert_eq!(holder.amount_claimed, 10_000_000);
+    assert_eq!(pool.total_claimed, pool.total_deposited);
+}
+
+#[test]
+fn late_claimer_receives_full_pro_rata_share() {
+    let mut pool = pool();
+    let mut early = position(&pool, 3);
+    let mut late = position(&pool, 1);
+
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+    assert_eq!(claim(&mut pool, &mut early), 750_000);
+
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+    assert_eq!(claim(&mut pool, &mut early), 750_000);
+
+    assert_eq!(claim(&mut pool, &mut late), 500_000);
+    assert_eq!(pool.available().unwrap(), 0);
+}
+
+#[test]
+fn resold_shares_keep_accrued_payouts_with_the_seller() {
+    let mut pool = pool();
+    let mut seller = position(&pool, TOTAL_SHARES);
+
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+
+    // Seller sells half their shares without claiming first
+    let mut buyer = position(&pool, 0);
+    seller.remove_shares(2, pool.reward_per_share).unwrap();
+    buyer.add_shares(2, pool.reward_per_share).unwrap();
+
+    pool.record_deposit(2_000_000, TOTAL_SHARES).unwrap();
+
+    assert_eq!(claim(&mut pool, &mut buyer), 1_000_000);
+    assert_eq!(claim(&mut pool, &mut seller), 2_000_000);
+    assert_eq!(pool.available().unwrap(), 0);
+}
+
+#[test]
+fn claimed_position_cannot_double_dip_after_resale() {
+    let mut pool = pool();
+    let mut seller = position(&pool, TOTAL_SHARES);
+
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+    assert_eq!(claim(&mut pool, &mut seller), 1_000_000);
+
+    let mut buyer = position(&pool, 0);
+    seller
+        .remove_shares(TOTAL_SHARES, pool.reward_per_share)
+        .unwrap();
+    buyer
+        .add_shares(TOTAL_SHARES, pool.reward_per_share)
+        .unwrap();
+
+    assert_eq!(claim(&mut pool, &mut buyer), 0);
+    assert_eq!(claim(&mut pool, &mut seller), 0);
+}
+
+#[test]
+fn removing_more_shares_than_credited_fails() {
+    let pool = pool();
+    let mut holder = position(&pool, 1);
+
+    assert!(holder.remove_shares(2, pool.reward_per_share).is_err());
+}
+
+#[test]
+fn rounding_never_pays_out_more_than_deposited() {
+    let mut pool = pool();
+    let shares = 3;
+    let deposits = [1, 2, 1_000_001, 7];
+    let mut holders: Vec<_> = (0..shares).map(|_| position(&pool, 1)).collect();
+
+    for amount in deposits {
+        pool.record_deposit(amount, shares).unwrap();
+        claim(&mut pool, &mut holders[0]);
+    }
+    for holder in holders.iter_mut() {
+        claim(&mut pool, holder);
+    }
+
+    // Each settlement rounds down by less than one unit
+    let settlements = deposits.len() as u64 + shares;
+    assert!(pool.total_claimed <= pool.total_deposited);
+    assert!(pool.available().unwrap() < settlements);
+}
+
+#[test]
+fn shares_transferred_outside_the_marketplace_follow_the_tokens() {
+    let mut pool = pool();
+    let mut alice = position(&pool, TOTAL_SHARES);
+    let mut bob = position(&pool, 0);
+
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+    assert_eq!(claim(&mut pool, &mut alice), 1_000_000);
+
+    // Alice sends half her tokens to Bob with a plain transfer
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+    // Bob holds tokens but nothing has been released to take up yet
+    assert_eq!(bob.reconcile(2, &mut pool).unwrap(), (0, 0));
+    assert_eq!(alice.reconcile(2, &mut pool).unwrap(), (2, 0));
+    assert_eq!(claim(&mut pool, &mut alice), 500_000);
+    assert_eq!(bob.reconcile(2, &mut pool).unwrap(), (0, 2));
+    assert_eq!(claim(&mut pool, &mut bob), 500_000);
+
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+    assert_eq!(alice.reconcile(2, &mut pool).unwrap(), (0, 0));
+    assert_eq!(claim(&mut pool, &mut alice), 500_000);
+    assert_eq!(claim(&mut pool, &mut bob), 500_000);
+    assert_eq!(pool.available().unwrap(), 0);
+}
+
+#[test]
+fn released_shares_are_taken_up_only_against_tokens_held() {
+    let mut pool = pool();
+    let mut alice = position(&pool, TOTAL_SHARES);
+    let mut bob = position(&pool, 0);
+    let mut carol = position(&pool, 0);
+
+    // Alice keeps one token and sends one each to Bob and Carol
+    alice.reconcile(2, &mut pool).unwrap();
+    pool.record_deposit(1_000_000, TOTAL_SHARES).unwrap();
+
+    // Bob holds a single token, so he takes a single share and half of
+    // what the two released shares accrued
+    assert_eq!(bob.reconcile(1, &mut pool).unwrap(), (0, 1));
+    assert_eq!(bob.reconcile(1, &mut pool).unwrap(), (0, 0));
+    assert_eq!(claim(&mut pool, &mut bob), 250_000);
+    assert_eq!(carol.reconcile(5, &mut pool).unwrap(), (0, 1));
+    assert_eq!(claim(&mut pool, &mut carol), 250_000);
+    assert_eq!(claim(&mut pool, &mut alice), 500_000);
+    assert_eq!(pool.released_shares, 0);
+    assert_eq!(pool.available().unwrap(), 0);
+}
+
+#[test]
+fn payout_schedule_allows_catching_up_but_not_prepaying() {
+    let mut pool = PayoutPool {
+        payout_interval_seconds: 100,
+        ..pool()
+    };
+    assert!(!pool.is_overdue(i64::MAX));
+
+    pool.start_schedule(1_000);
+    assert_eq!(pool.next_payout_due, 1_100);
+    assert!(!pool.is_overdue(1_100));
+    assert!(pool.is_overdue(1_101));
+
+    // Two periods missed: each deposit settles one of them
+    pool.advance_schedule(1_350);
+    assert_eq!(pool.next_payout_due, 1_200);
+    assert!(pool.is_overdue(1_350));
+    pool.advance_schedule(1_350);
+    assert_eq!(pool.next_payout_due, 1_300);
+    pool.advance_schedule(1_350);
+    assert_eq!(pool.next_payout_due, 1_400);
+    assert!(!pool.is_overdue(1_350));
+
+    // Deposits ahead of schedule never push the deadline past one interval out
+    pool.advance_schedule(1_350);
+    assert_eq!(pool.next_payout_due, 1_450);
+}
+
+#[test]
+fn pool_without_a_schedule_is_never_overdue() {
+    let mut pool = pool();
+    pool.start_schedule(1_000);
+    pool.advance_schedule(5_000);
+    assert_eq!(pool.next_payout_due, 0);
+    assert!(!pool.is_overdue(i64::MAX));
+}
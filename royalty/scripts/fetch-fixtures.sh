@@ -0,0 +1,47 @@
+#!/usr/bin/env bash
+# Fetch the program binaries the SVM suite loads from programs/royalties/tests/fixtures
+# and check them against the checksums pinned in SHA256SUMS there.
+#
+#   scripts/fetch-fixtures.sh           dump missing binaries, verify every pinned one
+#   scripts/fetch-fixtures.sh --update  re-dump every binary and re-pin its checksum
+#
+# A binary without a pinned checksum is pinned on first fetch; commit the binary and
+# SHA256SUMS together. Set RPC_URL to dump from somewhere other than mainnet-beta.
+set -euo pipefail
+
+cd "$(dirname "$0")/../programs/royalties/tests/fixtures"
+
+RPC_URL="${RPC_URL:-https://api.mainnet-beta.solana.com}"
+UPDATE=false
+if [[ "${1:-}" == "--update" ]]; then
+  UPDATE=true
+fi
+
+# file name -> program id
+declare -A PROGRAMS=(
+  [mpl_token_metadata.so]=metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s
+)
+
+touch SHA256SUMS
+for file in "${!PROGRAMS[@]}"; do
+  if $UPDATE || [[ ! -f "$file" ]]; then
+    echo "Dumping ${PROGRAMS[$file]} to $file"
+    solana program dump --url "$RPC_URL" "${PROGRAMS[$file]}" "$file"
+  fi
+
+  actual="$(sha256sum "$file" | cut -d' ' -f1)"
+  pinned="$(awk -v f="$file" '$2 == f { print $1 }' SHA256SUMS)"
+  if $UPDATE || [[ -z "$pinned" ]]; then
+    grep -v "  $file\$" SHA256SUMS > SHA256SUMS.tmp || true
+    echo "$actual  $file" >> SHA256SUMS.tmp
+    sort -k2 SHA256SUMS.tmp > SHA256SUMS
+    rm SHA256SUMS.tmp
+    echo "Pinned $file at $actual; commit it together with SHA256SUMS"
+  elif [[ "$actual" != "$pinned" ]]; then
+    echo "$file does not match its pinned checksum" >&2
+    echo "  pinned $pinned" >&2
+    echo "  actual $actual" >&2
+    echo "If Metaplex shipped a new release, review it and rerun with --update to re-pin" >&2
+    exit 1
+  fi
+done